@@ -11,9 +11,12 @@ impl Emitter<'_> {
         let mut res = Vec::new();
 
         for stmt in block.stmts {
+            res.extend(self.line_directive(stmt.span));
             res.extend(self.emit_stmt(ctx, stmt));
         }
 
+        res.extend(self.line_directive(block.branch.span));
+
         match block.branch.node {
             BranchNode::Return(rets) => match (&rets[..], write_to) {
                 ([], None) => res.push("return;".into()),
@@ -36,7 +39,8 @@ impl Emitter<'_> {
 
                     let rets: Vec<_> = rets
                         .into_iter()
-                        .map(|value| self.emit_value(value))
+                        .enumerate()
+                        .map(|(index, value)| format!(".f{index} = {}", self.emit_value(value)))
                         .collect();
                     let rets = rets.join(", ");
 
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+
+use zippy_common::message::Span;
+use zippy_common::mir::{Type, TypeId};
+use zippy_common::names::{Actual, Name};
+
+use crate::mangle::mangle;
+
+use super::{reserved_symbol, Emitter};
+
+/// Marks a function as part of the interface of a library.
+const EXPORT_MACRO: &str = "\
+#if defined(_WIN32)
+#define ZIPPY_EXPORT __declspec(dllexport)
+#else
+#define ZIPPY_EXPORT __attribute__((visibility(\"default\")))
+#endif
+";
+
+/// A C header declaring the exported functions of a library. Types get their
+/// own names in the header, prefixed with the name of the library, since the
+/// names of the types in the generated code are neither stable nor unique.
+#[derive(Debug)]
+pub struct Header {
+    prefix: String,
+    includes: Vec<&'static str>,
+    type_map: HashMap<TypeId, String>,
+    type_name: usize,
+    typedefs: String,
+    functions: String,
+}
+
+impl Header {
+    pub fn new(name: &str) -> Self {
+        let prefix = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        Self {
+            prefix,
+            includes: Vec::new(),
+            type_map: HashMap::new(),
+            type_name: 0,
+            typedefs: String::new(),
+            functions: String::new(),
+        }
+    }
+
+    pub fn build(self) -> String {
+        let guard = format!("{}_H", self.prefix.to_ascii_uppercase());
+
+        let mut result = format!("#ifndef {guard}\n#define {guard}\n\n");
+        for include in self.includes {
+            result.push_str(&format!("#include <{include}>\n"));
+        }
+
+        result.push('\n');
+        result.push_str(&self.typedefs);
+        result.push('\n');
+        result.push_str(&self.functions);
+        result.push_str(&format!("\n#endif /* {guard} */\n"));
+
+        result
+    }
+
+    /// Check whether `symbol` is used by the header itself, either as the name
+    /// of a type or of the include guard.
+    fn reserved(&self, symbol: &str) -> Option<String> {
+        let typename = symbol
+            .strip_prefix(&self.prefix)
+            .and_then(|rest| rest.strip_prefix("_t"))
+            .is_some_and(|index| !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()));
+        let guard = symbol == format!("{}_H", self.prefix.to_ascii_uppercase());
+
+        (typename || guard).then(|| format!("note: `{symbol}` is used by the header"))
+    }
+
+    fn typedef(&mut self, pre: &str, post: &str) -> String {
+        let name = format!("{}_t{}", self.prefix, self.type_name);
+        self.type_name += 1;
+        self.typedefs
+            .push_str(&format!("typedef {pre} {name}{post};\n"));
+        name
+    }
+}
+
+impl Emitter<'_> {
+    /// Emit a function with an unmangled name for every exported function,
+    /// which calls the actual function after making sure every value has
+    /// been initialized. Each one is also declared in the given header.
+    /// Functions whose name can't be used in C, or is already used by another
+    /// exported or external function, are reported instead.
+    pub fn emit_exports(&mut self, header: &mut Header, exports: &[Name]) {
+        let mut symbols: HashMap<String, Span> = self
+            .externs
+            .iter()
+            .map(|(name, symbol)| (symbol.clone(), self.names.get_span(name)))
+            .collect();
+
+        let needs_init = !self.inits.is_empty();
+
+        if needs_init {
+            self.res.push_str("static void zippy_init(void) {\n");
+            self.res.push_str("\tstatic int done = 0;\n");
+            self.res.push_str("\tif (done) {\n");
+            self.res.push_str("\t\treturn;\n");
+            self.res.push_str("\t}\n");
+            self.res.push_str("\tdone = 1;\n");
+            self.res.push_str(&self.inits);
+            self.res.push_str("}\n");
+        }
+
+        if !exports.is_empty() {
+            self.auxilliary.push_str(EXPORT_MACRO);
+        }
+
+        for name in exports {
+            let ty = self.context.get(name);
+            let Type::Fun(args, rets) = self.types.get(&ty).clone() else {
+                let span = self.names.get_span(name);
+                self.messages.at(span).compile_export_not_a_function();
+                continue;
+            };
+
+            let symbol = match &self.names.get_path(name).1 {
+                Actual::Lit(name) => name.clone(),
+                _ => unreachable!("exported functions are always named in the source"),
+            };
+
+            let span = self.names.get_span(name);
+            if let Some(reason) = reserved_symbol(&symbol).or_else(|| header.reserved(&symbol)) {
+                self.messages
+                    .at(span)
+                    .compile_reserved_symbol(&symbol, reason);
+                continue;
+            }
+
+            if let Some(previous) = symbols.get(&symbol) {
+                self.messages
+                    .at(span)
+                    .compile_duplicate_symbol(&symbol, *previous);
+                continue;
+            }
+
+            symbols.insert(symbol.clone(), span);
+
+            let ret = match &rets[..] {
+                [] => None,
+                [ret] => Some(*ret),
+                _ => Some(self.types.add(Type::Product(rets))),
+            };
+
+            let params: Vec<_> = (0..args.len()).map(|index| format!("a{index}")).collect();
+            let call = format!("{}({})", mangle(self.names, name), params.join(", "));
+
+            let ret_name = match ret {
+                Some(ret) => self.typename(&ret).to_string(),
+                None => "void".into(),
+            };
+
+            let args_list: Vec<_> = args
+                .iter()
+                .zip(params.iter())
+                .map(|(ty, param)| format!("{} {param}", self.typename(ty)))
+                .collect();
+
+            self.res.push_str(&format!(
+                "ZIPPY_EXPORT {ret_name} {symbol}({}) {{\n",
+                parameter_list(args_list)
+            ));
+
+            if needs_init {
+                self.res.push_str("\tzippy_init();\n");
+            }
+
+            if ret.is_some() {
+                self.res.push_str(&format!("\treturn {call};\n"));
+            } else {
+                self.res.push_str(&format!("\t{call};\n"));
+            }
+
+            self.res.push_str("}\n");
+
+            let ret_name = match ret {
+                Some(ret) => self.header_typename(header, &ret),
+                None => "void".into(),
+            };
+
+            let args_list: Vec<_> = args
+                .iter()
+                .zip(params.iter())
+                .map(|(ty, param)| format!("{} {param}", self.header_typename(header, ty)))
+                .collect();
+
+            header.functions.push_str(&format!(
+                "{ret_name} {symbol}({});\n",
+                parameter_list(args_list)
+            ));
+        }
+    }
+
+    /// Get the name of a type in the header, adding a typedef for it and every
+    /// type it is made of if there isn't one already.
+    fn header_typename(&mut self, header: &mut Header, ty: &TypeId) -> String {
+        if let Some(name) = header.type_map.get(ty) {
+            return name.clone();
+        }
+
+        let name = match self.types.get(ty).clone() {
+            Type::Range(..) => {
+                if !header.includes.contains(&"stdint.h") {
+                    header.includes.push("stdint.h");
+                }
+
+                let integer = self.typename(ty).to_string();
+                header.typedef(&integer, "")
+            }
+
+            // The fields are declared in the same order as in the generated
+            // code, so that both structs are laid out the same way.
+            Type::Product(ties) => {
+                let fields: Vec<_> = self
+                    .field_order(&ties)
+                    .into_iter()
+                    .map(|index| {
+                        let ty = self.header_typename(header, &ties[index]);
+                        format!("{ty} f{index};")
+                    })
+                    .collect();
+
+                let layout = self.layout_of(ty);
+                header.typedefs.push_str(&format!(
+                    "/* {} bytes, aligned to {} */\n",
+                    layout.size, layout.align
+                ));
+
+                header.typedef(&format!("struct {{\n\t{}\n}}", fields.join("\n\t")), "")
+            }
+
+            Type::Fun(args, rets) => {
+                let ret = match &rets[..] {
+                    [] => "void".into(),
+                    [ret] => self.header_typename(header, ret),
+                    _ => {
+                        let ret = self.types.add(Type::Product(rets.clone()));
+                        self.header_typename(header, &ret)
+                    }
+                };
+
+                let args: Vec<_> = args
+                    .iter()
+                    .map(|ty| self.header_typename(header, ty))
+                    .collect();
+
+                header.typedef(
+                    &format!("{ret} (*"),
+                    &format!(")({})", parameter_list(args)),
+                )
+            }
+
+            Type::Text => header.typedef("const char *", ""),
+
+            Type::Number | Type::Invalid => header.typedef("void *", ""),
+        };
+
+        header.type_map.insert(*ty, name.clone());
+        name
+    }
+}
+
+fn parameter_list(params: Vec<String>) -> String {
+    if params.is_empty() {
+        "void".into()
+    } else {
+        params.join(", ")
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use zippy_common::hir::Extern;
+use zippy_common::message::Span;
+use zippy_common::mir::pretty::Prettier;
+use zippy_common::mir::{Type, TypeId};
+use zippy_common::names::Name;
+
+use super::{reserved_symbol, Emitter};
+
+impl Emitter<'_> {
+    /// Declare the external functions among `reachable`, either by including
+    /// the header they were declared with or by writing a prototype based on
+    /// their types. Calls to them use the name of the function in C. The same
+    /// function may be declared more than once, but only with the same C
+    /// signature every time.
+    pub fn emit_externs(&mut self, reachable: &HashSet<Name>, externs: BTreeMap<Name, Extern>) {
+        let mut declared: HashMap<String, (Span, String)> = HashMap::new();
+
+        for (name, external) in externs {
+            if !reachable.contains(&name) {
+                continue;
+            }
+
+            let span = self.names.get_span(&name);
+            let ty = self.context.get(&name);
+
+            let Some((ret, args)) = self.extern_signature(&ty) else {
+                let ty = {
+                    let prettier = Prettier::new(self.names, self.types);
+                    prettier.pretty_type(&ty)
+                };
+
+                self.messages.at(span).compile_extern_unsupported_type(ty);
+                continue;
+            };
+
+            let symbol = external.symbol;
+            if let Some(reason) = reserved_symbol(&symbol) {
+                self.messages
+                    .at(span)
+                    .compile_reserved_symbol(&symbol, reason);
+                continue;
+            }
+
+            let signature = format!("{ret} {symbol}({args})");
+            if let Some((previous, previous_signature)) = declared.get(&symbol) {
+                if *previous_signature == signature {
+                    self.externs.insert(name, symbol);
+                } else {
+                    self.messages.at(span).compile_extern_signature_mismatch(
+                        &signature,
+                        *previous,
+                        previous_signature,
+                    );
+                }
+
+                continue;
+            }
+
+            declared.insert(symbol.clone(), (span, signature.clone()));
+            self.externs.insert(name, symbol);
+
+            if let Some(header) = external.header {
+                if !self.headers.contains(&header) {
+                    self.headers.push(header);
+                }
+
+                continue;
+            }
+
+            self.auxilliary.push_str(&format!("extern {signature};\n"));
+        }
+    }
+
+    /// Get the C return type and parameter list of an external function of
+    /// the given type, or `None` if it has no C equivalent. A C function
+    /// returns at most one value, or nothing at all.
+    fn extern_signature(&mut self, ty: &TypeId) -> Option<(String, String)> {
+        let Type::Fun(args, rets) = self.types.get(ty).clone() else {
+            return None;
+        };
+
+        let supported = rets.len() <= 1
+            && args
+                .iter()
+                .chain(rets.iter())
+                .all(|ty| matches!(self.types.get(ty), Type::Range(..) | Type::Text));
+
+        if !supported {
+            return None;
+        }
+
+        let ret = match &rets[..] {
+            [] => "void".to_string(),
+            [ret] => self.typename(ret).to_string(),
+            _ => unreachable!(),
+        };
+
+        let args: Vec<_> = args
+            .iter()
+            .map(|ty| self.typename(ty).to_string())
+            .collect();
+
+        let args = if args.is_empty() {
+            "void".to_string()
+        } else {
+            args.join(", ")
+        };
+
+        Some((ret, args))
+    }
+}
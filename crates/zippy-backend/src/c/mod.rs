@@ -1,17 +1,25 @@
 mod block;
+mod export;
+mod external;
 mod statement;
 mod types;
 mod value;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::time::Instant;
 
-use zippy_common::message::Messages;
-use zippy_common::mir::{discover, Context, Decls, StaticValue, Type, TypeId, Types};
+use zippy_common::message::{Messages, SourceMap, Span};
+use zippy_common::mir::{
+    discover, Context, Decls, StaticValue, StaticValueNode, Type, TypeId, Types,
+};
 use zippy_common::names::{Name, Names};
-use zippy_common::Driver;
+use zippy_common::sizes::{DataLayout, IntegerType};
+use zippy_common::{Driver, IrOutput, Pass};
 
 use crate::mangle::mangle;
 
+use self::export::Header;
+
 pub fn emit(
     driver: &mut impl Driver,
     names: &mut Names,
@@ -20,8 +28,11 @@ pub fn emit(
     entry: Option<Name>,
     decls: Decls,
 ) -> String {
-    let mut emitter = Emitter::new(names, types, context);
-    emitter.emit_decls(entry, decls);
+    let start = Instant::now();
+    let (layout, source_map) = (driver.data_layout(), driver.source_map());
+    let mut emitter = Emitter::new(names, types, context, layout, source_map);
+    let roots: Vec<_> = entry.into_iter().collect();
+    emitter.emit_decls(&roots, decls);
 
     if let Some(entry) = entry {
         emitter.emit_entry(entry);
@@ -29,12 +40,128 @@ pub fn emit(
 
     driver.report(emitter.messages.drain());
 
-    emitter.build()
+    let code = emitter.build();
+    driver.report_pass(Pass::Emit, start.elapsed());
+    driver.output_ir(IrOutput::C, || code.clone());
+    code
+}
+
+/// Emit a library in which each of the given functions can be called from C
+/// under its own name, along with a header declaring them, named after the
+/// library. Returns the code and the header.
+pub fn emit_library(
+    driver: &mut impl Driver,
+    names: &mut Names,
+    types: &mut Types,
+    context: &Context,
+    name: &str,
+    exports: &[Name],
+    decls: Decls,
+) -> (String, String) {
+    let start = Instant::now();
+    let (layout, source_map) = (driver.data_layout(), driver.source_map());
+    let mut emitter = Emitter::new(names, types, context, layout, source_map);
+    let mut header = Header::new(name);
+
+    emitter.emit_decls(exports, decls);
+    emitter.emit_exports(&mut header, exports);
+
+    driver.report(emitter.messages.drain());
+
+    let code = (emitter.build(), header.build());
+    driver.report_pass(Pass::Emit, start.elapsed());
+    driver.output_ir(IrOutput::C, || code.0.clone());
+    code
+}
+
+/// Emit a test harness for the given tests. The resulting program takes the
+/// index of a test in `tests` as its only argument, and exits successfully if
+/// and only if that test evaluates to zero. Any other argument makes it exit
+/// with 2. Each test is run in its own process, so that one test aborting
+/// doesn't affect the others.
+pub fn emit_tests(
+    driver: &mut impl Driver,
+    names: &mut Names,
+    types: &mut Types,
+    context: &Context,
+    tests: &[Name],
+    mut decls: Decls,
+) -> String {
+    let start = Instant::now();
+    let (layout, source_map) = (driver.data_layout(), driver.source_map());
+    let mut emitter = Emitter::new(names, types, context, layout, source_map);
+
+    let mut bodies = Vec::with_capacity(tests.len());
+    for (index, name) in tests.iter().enumerate() {
+        // Tests which evaluate to a function end up with the functions rather
+        // than the values.
+        match decls.values.get(name) {
+            Some(value) if matches!(emitter.types.get(&value.ty), Type::Range(..)) => {
+                emitter.tests.insert(*name);
+                bodies.push((index, *name, value.clone()));
+            }
+
+            _ => {
+                let span = emitter.names.get_span(name);
+                emitter.messages.at(span).compile_test_not_a_number();
+            }
+        }
+    }
+
+    let roots: Vec<_> = bodies.iter().map(|(_, name, _)| *name).collect();
+    decls.values.retain(|name, _| !emitter.tests.contains(name));
+    emitter.emit_decls(&roots, decls);
+    emitter.emit_harness(bodies);
+
+    driver.report(emitter.messages.drain());
+
+    let code = emitter.build();
+    driver.report_pass(Pass::Emit, start.elapsed());
+    driver.output_ir(IrOutput::C, || code.clone());
+    code
+}
+
+/// The keywords of C, up to C23, separated by spaces.
+const C_KEYWORDS: &str = "\
+    alignas alignof auto bool break case char const constexpr continue default do double else \
+    enum extern false float for goto if inline int long nullptr register restrict return short \
+    signed sizeof static static_assert struct switch thread_local true typedef typeof \
+    typeof_unqual union unsigned void volatile while";
+
+/// The names the generated code uses from the C headers it includes, along
+/// with `main`.
+const C_RESERVED: &[&str] = &[
+    "main", "raise", "strtol", "errno", "intmax_t", "int8_t", "int16_t", "int32_t", "int64_t",
+    "uint8_t", "uint16_t", "uint32_t", "uint64_t",
+];
+
+/// The prefixes of the names the generated code defines itself. Mangled names
+/// start with `Z_`.
+const GENERATED_PREFIXES: &[&str] = &["zippy_", "ZIPPY_", "Z_"];
+
+/// Check whether an exported or external function can be called `symbol` in
+/// C, giving a note saying why not if it can't.
+fn reserved_symbol(symbol: &str) -> Option<String> {
+    if symbol.contains('\'') {
+        Some("note: names in C cannot contain `'`".into())
+    } else if C_KEYWORDS.split(' ').any(|keyword| keyword == symbol) {
+        Some(format!("note: `{symbol}` is a keyword in C"))
+    } else if C_RESERVED.contains(&symbol) {
+        Some(format!("note: `{symbol}` is used by the generated code"))
+    } else if GENERATED_PREFIXES
+        .iter()
+        .any(|prefix| symbol.starts_with(prefix))
+    {
+        Some("note: names starting with `zippy_`, `ZIPPY_` or `Z_` are reserved".into())
+    } else {
+        None
+    }
 }
 
 #[derive(Debug)]
 struct Emitter<'a> {
-    includes: HashSet<&'static str>,
+    /// Kept sorted, so that they are always included in the same order.
+    includes: BTreeSet<&'static str>,
     inits: String,
     auxilliary: String,
     res: String,
@@ -44,19 +171,48 @@ struct Emitter<'a> {
 
     type_map: HashMap<TypeId, String>,
     type_name: usize,
+
+    /// The integer type chosen for every range type so far.
+    range_types: HashMap<TypeId, IntegerType>,
+
     values: HashMap<Name, StaticValue>,
 
+    /// The tests being emitted, which are turned into functions rather than
+    /// static values.
+    tests: HashSet<Name>,
+
     has_invalid: bool,
 
+    /// The name of the static array holding every piece of text used so far.
+    texts: HashMap<String, String>,
+
+    /// The name in C of every external function which has been declared.
+    externs: HashMap<Name, String>,
+
+    /// Headers declaring external functions.
+    headers: Vec<String>,
+
+    layout: DataLayout,
+
+    /// Used to point the generated code back at the source, if debug info is
+    /// enabled.
+    source_map: Option<SourceMap>,
+
     names: &'a mut Names,
     types: &'a mut Types,
     context: &'a Context,
 }
 
 impl<'a> Emitter<'a> {
-    pub fn new(names: &'a mut Names, types: &'a mut Types, context: &'a Context) -> Self {
+    pub fn new(
+        names: &'a mut Names,
+        types: &'a mut Types,
+        context: &'a Context,
+        layout: DataLayout,
+        source_map: Option<SourceMap>,
+    ) -> Self {
         Self {
-            includes: HashSet::new(),
+            includes: BTreeSet::new(),
             inits: String::new(),
             auxilliary: String::new(),
             res: String::new(),
@@ -66,10 +222,21 @@ impl<'a> Emitter<'a> {
 
             type_map: HashMap::new(),
             type_name: 0,
+            range_types: HashMap::new(),
             values: HashMap::new(),
 
+            tests: HashSet::new(),
+
             has_invalid: false,
 
+            texts: HashMap::new(),
+
+            externs: HashMap::new(),
+            headers: Vec::new(),
+
+            layout,
+            source_map,
+
             names,
             types,
             context,
@@ -82,6 +249,10 @@ impl<'a> Emitter<'a> {
             result.push_str(&format!("#include <{include}>\n"));
         }
 
+        for header in self.headers {
+            result.push_str(&format!("#include \"{header}\"\n"));
+        }
+
         result.push_str(&self.auxilliary);
         result.push_str(&self.typedefs);
         result.push_str(&self.res);
@@ -89,11 +260,11 @@ impl<'a> Emitter<'a> {
         result
     }
 
-    pub fn emit_decls(&mut self, entry: Option<Name>, decls: Decls) {
+    pub fn emit_decls(&mut self, roots: &[Name], decls: Decls) {
         assert!(decls.defs.is_empty());
 
-        let (reachable, in_types) = if entry.is_some() {
-            discover(self.types, self.context, entry, &decls)
+        let (reachable, in_types) = if !roots.is_empty() {
+            discover(self.types, self.context, roots, &decls)
         } else {
             self.res.push_str("// (no code generated)\n");
             return;
@@ -108,6 +279,8 @@ impl<'a> Emitter<'a> {
             self.values.insert(name, value.clone());
         }
 
+        self.emit_externs(&reachable, decls.externs);
+
         for (name, value) in decls.values.iter() {
             if !reachable.contains(name) {
                 continue;
@@ -158,19 +331,78 @@ impl<'a> Emitter<'a> {
         let needs_init = !self.inits.is_empty();
 
         if needs_init {
-            self.res.push_str("void init(void) {\n");
+            self.res.push_str("static void zippy_init(void) {\n");
             self.res.push_str(&self.inits);
             self.res.push_str("}\n");
         }
 
         self.res.push_str("int main(void) {\n");
         if needs_init {
-            self.res.push_str("\tinit();\n");
+            self.res.push_str("\tzippy_init();\n");
         }
         self.res.push_str(&format!("\treturn (int){entry}(0);\n"));
         self.res.push_str("}\n");
     }
 
+    pub fn emit_harness(&mut self, tests: Vec<(usize, Name, StaticValue)>) {
+        let mut cases = String::new();
+
+        for (index, name, value) in tests {
+            let mangled = mangle(self.names, &name);
+            let ty = self.typename(&value.ty).to_string();
+
+            let lines = match value.node {
+                StaticValueNode::Num(i) => {
+                    format!("return {};", self.scaled(&value.ty, value.span, i))
+                }
+                StaticValueNode::Text(_) => unreachable!(),
+                StaticValueNode::LateInit(block) => self.emit_block(name, None, block).join("\n\t"),
+            };
+
+            self.define(&mangled, &ty, "(void)");
+            self.res.push_str(&format!(" {{\n\t{lines}\n}}\n"));
+
+            cases.push_str(&format!("\tcase {index}: return {mangled}() != 0;\n"));
+        }
+
+        self.includes.insert("errno.h");
+        self.includes.insert("stdlib.h");
+
+        let needs_init = !self.inits.is_empty();
+
+        if needs_init {
+            self.res.push_str("static void zippy_init(void) {\n");
+            self.res.push_str(&self.inits);
+            self.res.push_str("}\n");
+        }
+
+        self.res.push_str("int main(int argc, char **argv) {\n");
+        self.res.push_str("\tif (argc < 2) {\n");
+        self.res.push_str("\t\treturn 2;\n");
+        self.res.push_str("\t}\n");
+
+        // The whole argument has to be a number, so that nothing else is read
+        // as the first test.
+        self.res.push_str("\tchar *zippy_end;\n");
+        self.res.push_str("\terrno = 0;\n");
+        self.res
+            .push_str("\tlong zippy_index = strtol(argv[1], &zippy_end, 10);\n");
+        self.res
+            .push_str("\tif (errno != 0 || zippy_end == argv[1] || *zippy_end != '\\0') {\n");
+        self.res.push_str("\t\treturn 2;\n");
+        self.res.push_str("\t}\n");
+
+        if needs_init {
+            self.res.push_str("\tzippy_init();\n");
+        }
+
+        self.res.push_str("\tswitch (zippy_index) {\n");
+        self.res.push_str(&cases);
+        self.res.push_str("\tdefault: return 2;\n");
+        self.res.push_str("\t}\n");
+        self.res.push_str("}\n");
+    }
+
     fn define_value(&mut self, mutable: bool, name: &Name) {
         let mangled = mangle(self.names, name);
 
@@ -224,6 +456,14 @@ impl<'a> Emitter<'a> {
         self.define(&mangled, &ret, &args);
     }
 
+    /// Get the name of a value or function in the generated code.
+    fn name_of(&self, name: &Name) -> String {
+        match self.externs.get(name) {
+            Some(symbol) => symbol.clone(),
+            None => mangle(self.names, name),
+        }
+    }
+
     fn declaration(&mut self) {
         self.res.push_str(";\n");
     }
@@ -232,14 +472,23 @@ impl<'a> Emitter<'a> {
         self.res.push_str(&format!("static {pre} {name}{post}"));
     }
 
+    /// Get a `#line` directive pointing at the start of the given span, if
+    /// debug info is enabled.
+    fn line_directive(&self, span: Span) -> Option<String> {
+        let (name, line) = self.source_map.as_ref()?.locate(span)?;
+        let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+        Some(format!("#line {line} \"{name}\""))
+    }
+
     fn invalid(&mut self) -> &'static str {
-        let name = "invalid";
+        let name = "zippy_invalid";
 
         if !self.has_invalid {
             self.has_invalid = true;
 
             self.includes.insert("signal.h");
-            self.auxilliary.push_str("void * invalid(void) {\n");
+            self.auxilliary
+                .push_str("static void * zippy_invalid(void) {\n");
             self.auxilliary.push_str("\traise(SIGABRT);\n");
             self.auxilliary.push_str("\treturn (void *) 0;\n");
             self.auxilliary.push_str("}\n");
@@ -11,7 +11,7 @@ impl Emitter<'_> {
 
         match stmt.node {
             StmtNode::Apply { names, fun, args } => {
-                let fun = mangle(self.names, &fun);
+                let fun = self.name_of(&fun);
                 let args: Vec<_> = args
                     .into_iter()
                     .map(|value| self.emit_value(value))
@@ -53,7 +53,8 @@ impl Emitter<'_> {
             StmtNode::Tuple { name, values } => {
                 let values: Vec<_> = values
                     .into_iter()
-                    .map(|value| self.emit_value(value))
+                    .enumerate()
+                    .map(|(index, value)| format!(".f{index} = {}", self.emit_value(value)))
                     .collect();
                 let values = values.join(", ");
 
@@ -75,10 +76,30 @@ impl Emitter<'_> {
                 res.push(format!("{ty} {mangled} = {of}.f{at};"));
             }
 
-            StmtNode::Coerce { name, of, to, .. } => {
+            StmtNode::Neg { name, of } => {
+                let mangled = mangle(self.names, &name);
+
+                // The number is negated after widening it, since its own type
+                // is unsigned for any range which doesn't go below zero.
+                let ty = self.context.get(&name);
+                let of_type = self.context.get(&of);
+                let of = mangle(self.names, &of);
+                let of = self.widened(stmt.span, &of, &of_type, &ty);
+                let ty = self.typename(&ty);
+
+                res.push(format!("{ty} {mangled} = ({ty}) -{of};"));
+            }
+
+            StmtNode::Coerce { name, of, from, to } => {
                 let of = mangle(self.names, &of);
                 let mangled = mangle(self.names, &name);
 
+                let of = if self.resolution(&from) == self.resolution(&to) {
+                    of
+                } else {
+                    self.widened(stmt.span, &of, &from, &to)
+                };
+
                 let ty = self.typename(&to);
 
                 res.push(format!("{ty} {mangled} = ({ty}) {of};"));
@@ -93,3 +114,151 @@ impl Emitter<'_> {
         res
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zippy_common::message::{Messages, Span};
+    use zippy_common::mir::{
+        Context, Statement, StaticValue, StaticValueNode, StmtNode, Type, TypeId, Types,
+    };
+    use zippy_common::names::{Actual, Name, Names, Path};
+    use zippy_common::sizes::DataLayout;
+    use zippy_common::Number;
+
+    use super::Emitter;
+    use crate::mangle::mangle;
+
+    /// The names and types used by a statement, along with the bounds of its
+    /// range types.
+    struct Program {
+        names: Names,
+        types: Types,
+        context: Context,
+        bounds: Vec<(Name, StaticValue)>,
+        root: Name,
+    }
+
+    impl Program {
+        fn new() -> Self {
+            let mut names = Names::new();
+            let root = names.root();
+
+            Self {
+                names,
+                types: Types::new(),
+                context: Context::new(),
+                bounds: Vec::new(),
+                root,
+            }
+        }
+
+        fn range(&mut self, lo: i64, hi: i64, step: Option<Number>) -> TypeId {
+            let lo = self.number(lo.into());
+            let hi = self.number(hi.into());
+            let step = step.map(|step| self.number(step));
+            self.types.add(Type::Range(lo, hi, step))
+        }
+
+        fn number(&mut self, value: Number) -> Name {
+            let span = Span::new(0, 0, 0);
+            let name = self.names.fresh(span, self.root);
+            let ty = self.types.add(Type::Number);
+            let node = StaticValueNode::Num(value);
+
+            self.context.add(name, ty);
+            self.bounds.push((name, StaticValue { node, span, ty }));
+            name
+        }
+
+        fn value(&mut self, name: &str, ty: TypeId) -> Name {
+            let span = Span::new(0, 0, 0);
+            let name = self
+                .names
+                .add(span, Path::new(self.root, Actual::Lit(name.into())));
+
+            self.context.add(name, ty);
+            name
+        }
+
+        fn emit(&mut self, node: StmtNode, ty: TypeId) -> (Vec<String>, Messages) {
+            let root = self.root;
+            let layout = DataLayout::host();
+            let mut emitter = Emitter::new(
+                &mut self.names,
+                &mut self.types,
+                &self.context,
+                layout,
+                None,
+            );
+
+            emitter.values.extend(self.bounds.iter().cloned());
+
+            let span = Span::new(0, 0, 0);
+            let lines = emitter.emit_stmt(root, Statement { node, span, ty });
+            (lines, emitter.messages.drain())
+        }
+    }
+
+    #[test]
+    fn negation_is_widened() {
+        let mut program = Program::new();
+        let small = program.range(0, 10, None);
+        let negative = program.range(-10, 0, None);
+        let x = program.value("x", small);
+        let y = program.value("y", negative);
+
+        let (lines, messages) = program.emit(StmtNode::Neg { name: y, of: x }, negative);
+
+        let (x, y) = (mangle(&program.names, &x), mangle(&program.names, &y));
+        assert_eq!(lines, [format!("int8_t {y} = (int8_t) -((intmax_t) {x});")]);
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn coercion_to_a_finer_resolution_is_exact() {
+        let mut program = Program::new();
+        let halves = program.range(0, 10, Some(Number::from(1) / Number::from(2)));
+        let quarters = program.range(0, 10, Some(Number::from(1) / Number::from(4)));
+        let x = program.value("x", halves);
+        let y = program.value("y", quarters);
+
+        let node = StmtNode::Coerce {
+            name: y,
+            of: x,
+            from: halves,
+            to: quarters,
+        };
+        let (lines, messages) = program.emit(node, quarters);
+
+        let (x, y) = (mangle(&program.names, &x), mangle(&program.names, &y));
+        assert_eq!(
+            lines,
+            [format!("uint8_t {y} = (uint8_t) ((intmax_t) {x} * 2);")]
+        );
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn coercion_to_a_coarser_resolution_is_rejected() {
+        let mut program = Program::new();
+        let quarters = program.range(0, 10, Some(Number::from(1) / Number::from(4)));
+        let halves = program.range(0, 10, Some(Number::from(1) / Number::from(2)));
+        let x = program.value("x", quarters);
+        let y = program.value("y", halves);
+
+        let node = StmtNode::Coerce {
+            name: y,
+            of: x,
+            from: quarters,
+            to: halves,
+        };
+        let (_, messages) = program.emit(node, halves);
+
+        let codes: Vec<_> = messages
+            .msgs
+            .iter()
+            .map(|msg| msg.code.as_deref())
+            .collect();
+        assert_eq!(codes, [Some("EC10")]);
+    }
+}
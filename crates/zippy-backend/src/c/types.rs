@@ -2,27 +2,14 @@ use lazy_static::lazy_static;
 use zippy_common::message::{Messages, Span};
 use zippy_common::mir::{StaticValueNode, Type, TypeId};
 use zippy_common::names::Name;
+use zippy_common::sizes::{IntegerType, Layout};
 use zippy_common::Number;
 
 use super::Emitter;
 
 lazy_static! {
     static ref ZERO: Number = 0i32.into();
-}
-
-macro_rules! within {
-    ($v:expr, $w:expr, $t:ty) => {
-        $v >= <$t>::MIN && $v <= <$t>::MAX && $w >= <$t>::MIN && $w <= <$t>::MAX
-    };
-}
-
-macro_rules! range_to_type {
-    ( $lo:expr, $hi:expr, $($t:ty => $value:tt),*, else => $fallback:expr ) => {
-        match () {
-            $( _ if within!($lo, $hi, $t) => $value ),*,
-            _ => $fallback,
-        }
-    };
+    static ref ONE: Number = 1i32.into();
 }
 
 impl Emitter<'_> {
@@ -35,19 +22,55 @@ impl Emitter<'_> {
         self.type_map.get(ty).unwrap()
     }
 
+    /// Make a struct with a field for every type, declared in the order which
+    /// needs the least padding on the target. The fields are still named after
+    /// their index in `of`.
     pub fn make_struct(&mut self, of: &[TypeId]) -> String {
-        let ties: Vec<_> = of
-            .iter()
-            .enumerate()
-            .map(|(ndx, ty)| format!("{} f{ndx};", self.typename(ty)))
+        let ties: Vec<_> = self
+            .field_order(of)
+            .into_iter()
+            .map(|ndx| format!("{} f{ndx};", self.typename(&of[ndx])))
             .collect();
 
         format!("struct {{\n\t{}\n}}", ties.join("\n\t"))
     }
 
+    /// Get the order in which the fields of a struct made of the given types
+    /// are declared.
+    pub fn field_order(&mut self, of: &[TypeId]) -> Vec<usize> {
+        let fields: Vec<_> = of.iter().map(|ty| self.layout_of(ty)).collect();
+        self.layout.field_order(&fields)
+    }
+
+    /// Get the size and alignment of a type on the target.
+    pub fn layout_of(&mut self, ty: &TypeId) -> Layout {
+        match self.types.get(ty).clone() {
+            Type::Range(..) => {
+                self.typename(ty);
+                self.layout.scalar(self.range_types[ty].size)
+            }
+
+            Type::Product(ties) => {
+                let fields: Vec<_> = self
+                    .field_order(&ties)
+                    .into_iter()
+                    .map(|ndx| self.layout_of(&ties[ndx]))
+                    .collect();
+
+                self.layout.struct_layout(&fields).0
+            }
+
+            Type::Fun(..) | Type::Text | Type::Number | Type::Invalid => self.layout.pointer(),
+        }
+    }
+
     fn make_typename(&mut self, ty: &TypeId) -> String {
         match self.types.get(ty) {
-            Type::Range(lo, hi) => self.make_integer_type(*lo, *hi),
+            Type::Range(lo, hi, step) => {
+                let (name, int) = self.make_integer_type(*lo, *hi, *step);
+                self.range_types.insert(*ty, int);
+                name
+            }
 
             Type::Product(ties) => {
                 let ty = self.make_struct(&ties.clone());
@@ -79,6 +102,8 @@ impl Emitter<'_> {
                 name
             }
 
+            Type::Text => "const char *".into(),
+
             Type::Number => {
                 unreachable!("values of type <number> should never be reachable from user code")
             }
@@ -91,30 +116,58 @@ impl Emitter<'_> {
         }
     }
 
-    fn make_integer_type(&mut self, lo: Name, hi: Name) -> String {
+    /// Get the name of the integer type for a range, along with the type.
+    /// Ranges with a resolution are stored as multiples of it, so the bounds
+    /// are scaled down by it first.
+    fn make_integer_type(
+        &mut self,
+        lo: Name,
+        hi: Name,
+        step: Option<Name>,
+    ) -> (String, IntegerType) {
         let lo_span = self.names.get_span(&lo);
         let hi_span = self.names.get_span(&hi);
 
         let mut messages = Messages::new();
 
+        let step = match step {
+            Some(step) => {
+                let span = self.names.get_span(&step);
+                self.get_resolution(&mut messages, span, &step)
+            }
+
+            None => ONE.clone(),
+        };
+
         let (lo, _) = self.get_bounds(&mut messages, lo_span, &lo);
         let (_, hi) = self.get_bounds(&mut messages, hi_span, &hi);
 
-        let ty = range_to_type! {
-            lo.clone(), hi.clone(),
-            u8 => "unsigned char",
-            i8 => "signed char",
-            u16 => "unsigned short",
-            i16 => "signed short",
-            u32 => "unsigned",
-            i32 => "int",
-            i64 => "long long",
-            else => unreachable!()
+        let scaled_lo = lo / &step;
+        let scaled_hi = hi / &step;
+
+        for (bound, scaled, span) in [(lo, &scaled_lo, lo_span), (hi, &scaled_hi, hi_span)] {
+            if !is_integer(scaled) {
+                messages
+                    .at(span)
+                    .compile_not_a_multiple(&bound.to_string(), &step.to_string());
+            }
+        }
+
+        let ty = match self.layout.integer_type(&scaled_lo, &scaled_hi) {
+            Some(ty) => (ty.c_name(), ty),
+            None => {
+                let (lo, hi) = (lo.to_string(), hi.to_string());
+                messages.at(hi_span).compile_range_too_large(&lo, &hi);
+
+                let size = self.layout.long_long_size;
+                ("intmax_t".into(), IntegerType { signed: true, size })
+            }
         };
 
         self.messages.merge(messages);
+        self.includes.insert("stdint.h");
 
-        ty.into()
+        ty
     }
 
     fn get_bounds(&self, messages: &mut Messages, at: Span, name: &Name) -> (&Number, &Number) {
@@ -122,16 +175,17 @@ impl Emitter<'_> {
 
         match &value.node {
             StaticValueNode::Num(value) => (value, value),
+            StaticValueNode::Text(_) => unreachable!(),
             StaticValueNode::LateInit(block) => {
                 let ty = self.types.get(&block.ty);
                 match ty {
-                    Type::Range(lo, hi) => {
+                    Type::Range(lo, hi, _) => {
                         let (lo, _) = self.get_bounds(messages, value.span, lo);
                         let (_, hi) = self.get_bounds(messages, value.span, hi);
                         (lo, hi)
                     }
                     Type::Invalid => todo!(),
-                    Type::Fun(..) | Type::Product(..) => unreachable!(),
+                    Type::Fun(..) | Type::Product(..) | Type::Text => unreachable!(),
 
                     Type::Number => {
                         messages.at(at).compile_unconstrained_range();
@@ -142,6 +196,108 @@ impl Emitter<'_> {
         }
     }
 
+    /// Get the resolution of a range, which must be a positive number known
+    /// at compile time.
+    fn get_resolution(&self, messages: &mut Messages, at: Span, name: &Name) -> Number {
+        let value = self.values.get(name).unwrap();
+
+        match &value.node {
+            StaticValueNode::Num(step) if *step > *ZERO => step.clone(),
+            StaticValueNode::Num(step) => {
+                messages
+                    .at(at)
+                    .compile_invalid_resolution(&step.to_string());
+                ONE.clone()
+            }
+
+            StaticValueNode::LateInit(_) => {
+                messages.at(at).compile_unconstrained_range();
+                ONE.clone()
+            }
+
+            StaticValueNode::Text(_) => unreachable!(),
+        }
+    }
+
+    /// Get the resolution of a type, which is one for anything but a range
+    /// with a valid resolution. Invalid resolutions are reported when the
+    /// type itself is made.
+    pub fn resolution(&self, ty: &TypeId) -> Number {
+        let Type::Range(_, _, Some(step)) = self.types.get(ty) else {
+            return ONE.clone();
+        };
+
+        match &self.values.get(step).unwrap().node {
+            StaticValueNode::Num(step) if *step > *ZERO => step.clone(),
+            _ => ONE.clone(),
+        }
+    }
+
+    /// Write a number as it is stored in a value of the given type, which for
+    /// a range with a resolution is the number of times the resolution fits
+    /// into it. Numbers which aren't a multiple of it are reported, and
+    /// written as an invalid value.
+    pub fn scaled(&mut self, ty: &TypeId, at: Span, number: Number) -> String {
+        let step = self.resolution(ty);
+        let scaled = &number / &step;
+
+        if is_integer(&scaled) {
+            return format!("{scaled}");
+        }
+
+        self.messages
+            .at(at)
+            .compile_not_a_multiple(&number.to_string(), &step.to_string());
+
+        let invalid = self.invalid();
+        let ty = self.typename(ty);
+        format!("({ty}) {invalid}()")
+    }
+
+    /// Write a value of one numeric type as a value of another, widened so
+    /// that the type it came from doesn't bound it. Ranges with a different
+    /// resolution are rescaled, which is only exact if the new resolution
+    /// divides the old one. Other resolutions are reported, and give an
+    /// invalid value.
+    pub fn widened(&mut self, at: Span, of: &str, from: &TypeId, to: &TypeId) -> String {
+        let (step, new_step) = (self.resolution(from), self.resolution(to));
+        let factor = &step / &new_step;
+
+        if !is_integer(&factor) {
+            self.messages
+                .at(at)
+                .compile_inexact_rescale(&step.to_string(), &new_step.to_string());
+
+            let invalid = self.invalid();
+            return format!("((intmax_t) {invalid}())");
+        }
+
+        let wide = self.wide_type(from);
+
+        if factor == *ONE {
+            format!("(({wide}) {of})")
+        } else {
+            format!("(({wide}) {of} * {factor})")
+        }
+    }
+
+    /// Get the type which values of a type are widened to. Unsigned types as
+    /// large as `intmax_t` don't fit it, so they are widened to `uintmax_t`,
+    /// whose arithmetic still gives the right value whenever it fits the type
+    /// it ends up in.
+    fn wide_type(&mut self, ty: &TypeId) -> &'static str {
+        if let Type::Range(..) = self.types.get(ty) {
+            self.typename(ty);
+
+            let int = self.range_types[ty];
+            if !int.signed && int.size >= self.layout.long_long_size {
+                return "uintmax_t";
+            }
+        }
+
+        "intmax_t"
+    }
+
     fn typedef(&mut self, name: &str, pre: &str, post: &str) {
         self.typedefs
             .push_str(&format!("typedef {pre} {name}{post};\n"));
@@ -150,6 +306,10 @@ impl Emitter<'_> {
     fn fresh_typename(&mut self) -> String {
         let counter = self.type_name;
         self.type_name += 1;
-        format!("t{counter}")
+        format!("zippy_t{counter}")
     }
 }
+
+fn is_integer(number: &Number) -> bool {
+    *number.denominator_ref() == 1u32
+}
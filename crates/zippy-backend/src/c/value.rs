@@ -2,13 +2,13 @@ use zippy_common::mir::{StaticValue, StaticValueNode, Value, ValueNode};
 use zippy_common::names::Name;
 
 use super::Emitter;
-use crate::mangle::mangle;
 
 impl Emitter<'_> {
     pub fn emit_value(&mut self, value: Value) -> String {
         match value.node {
-            ValueNode::Num(i) => format!("{i}"),
-            ValueNode::Name(name) => mangle(self.names, &name),
+            ValueNode::Num(i) => self.scaled(&value.ty, value.span, i),
+            ValueNode::Text(text) => self.text(text),
+            ValueNode::Name(name) => self.name_of(&name),
             ValueNode::Invalid => {
                 let invalid = self.invalid();
                 let ty = self.typename(&value.ty);
@@ -19,7 +19,8 @@ impl Emitter<'_> {
 
     pub fn emit_static_value(&mut self, ctx: Name, value: StaticValue) -> Option<String> {
         Some(match value.node {
-            StaticValueNode::Num(i) => format!("{i}"),
+            StaticValueNode::Num(i) => self.scaled(&value.ty, value.span, i),
+            StaticValueNode::Text(text) => self.text(text),
             StaticValueNode::LateInit(block) => {
                 let init = self.emit_block(ctx, Some(ctx), block);
                 let init = init.join("\n\t");
@@ -29,4 +30,43 @@ impl Emitter<'_> {
             }
         })
     }
+
+    /// Get the name of a static array holding the given text, adding one if
+    /// the same text has not been used before.
+    pub fn text(&mut self, text: String) -> String {
+        if let Some(name) = self.texts.get(&text) {
+            return name.clone();
+        }
+
+        let name = format!("zippy_text{}", self.texts.len());
+        self.auxilliary.push_str(&format!(
+            "static const char {name}[] = \"{}\";\n",
+            escape(&text)
+        ));
+
+        self.texts.insert(text, name.clone());
+        name
+    }
+}
+
+/// Escape text for use in a C string literal. Question marks are escaped so
+/// they can't form trigraphs, and anything other than printable ASCII is
+/// written as octal escapes of its UTF-8 bytes, which unlike hex escapes can't
+/// run into the characters after them.
+fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+
+    for byte in text.bytes() {
+        match byte {
+            b'"' | b'\\' | b'?' => {
+                res.push('\\');
+                res.push(char::from(byte));
+            }
+
+            b' '..=b'~' => res.push(char::from(byte)),
+            _ => res.push_str(&format!("\\{byte:03o}")),
+        }
+    }
+
+    res
 }
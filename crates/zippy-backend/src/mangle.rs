@@ -9,6 +9,7 @@ pub fn mangle(names: &Names, name: &Name) -> String {
             Actual::Lit(name) => name.clone(),
             Actual::Generated(gen) => String::from(*gen),
             Actual::Root => unreachable!(),
+            Actual::File(_) => unreachable!(),
             Actual::Scope(_) => unreachable!(),
         }
     } else {
@@ -26,6 +27,7 @@ pub fn mangle(names: &Names, name: &Name) -> String {
             res.push_str(&match part {
                 Actual::Lit(name) => format!("_n{name}"),
                 Actual::Generated(id) => String::from(id),
+                Actual::File(id) => format!("_f{id}"),
                 Actual::Scope(id) => format!("_{}", id),
                 Actual::Root => String::new(),
             });
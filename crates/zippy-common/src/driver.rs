@@ -1,4 +1,7 @@
-use crate::message::Messages;
+use std::time::Duration;
+
+use crate::message::{File, Messages, SourceMap};
+use crate::sizes::DataLayout;
 
 pub trait Driver {
     fn report(&mut self, messages: Messages);
@@ -10,6 +13,13 @@ pub trait Driver {
     /// since generating it would usually be wasteful.
     fn output_ir(&mut self, at: IrOutput, data: impl FnOnce() -> String);
 
+    /// Find and read the file imported as `name` by the file `from`. Returns the
+    /// id and source text of that file, or `None` if it could not be found.
+    /// Importing the same file twice should give the same id.
+    fn load_import(&mut self, _from: File, _name: &str) -> Option<(File, String)> {
+        None
+    }
+
     fn entry_name(&mut self) -> Option<String> {
         None
     }
@@ -17,6 +27,24 @@ pub trait Driver {
     fn eval_amount(&mut self) -> EvalAmount {
         EvalAmount::Full
     }
+
+    /// If debug info should be emitted, get the names and lines of every source
+    /// file, so that the generated code can point back at the source.
+    fn source_map(&mut self) -> Option<SourceMap> {
+        None
+    }
+
+    /// The data layout of the target being compiled for.
+    fn data_layout(&mut self) -> DataLayout {
+        DataLayout::host()
+    }
+
+    /// Report how long a pass of the compiler took. A pass may be reported
+    /// more than once, such as when several files are lexed.
+    fn report_pass(&mut self, _pass: Pass, _time: Duration) {}
+
+    /// Report the size of the program at some point during compilation.
+    fn report_stat(&mut self, _stat: Stat, _count: usize) {}
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -26,7 +54,99 @@ pub enum EvalAmount {
     None,
 }
 
+/// An intermediate representation given to [`Driver::output_ir`].
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum IrOutput {
+    /// The tokens of a file, each with its span.
+    Tokens(File),
+
+    /// The concrete syntax tree of a file.
+    Cst(File),
+
+    /// The declarations of a file before names are resolved.
+    Hir(File),
+
+    /// The declarations of the whole program after names are resolved.
+    ResolvedHir,
+
+    /// The kind of every type definition.
+    Kinds,
+
+    /// The type of every value definition after typechecking.
+    Thir,
+
+    /// The dependency graph between definitions, as a Graphviz graph.
+    DepsDot,
+
+    /// The MIR after the named pass.
     Mir(&'static str),
+
+    /// The control flow of the MIR after the named pass, as a Graphviz graph.
+    MirDot(&'static str),
+
+    /// The generated C code.
+    C,
+}
+
+/// A pass of the compiler, as reported by [`Driver::report_pass`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Pass {
+    Lex,
+    Parse,
+    Resolve,
+    Kindck,
+    Typeck,
+    Bridge,
+    Lower,
+    Flatten,
+    Hoist,
+    Eval,
+    Emit,
+
+    /// Compiling the emitted C code with an external compiler.
+    Compile,
+}
+
+impl Pass {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pass::Lex => "lex",
+            Pass::Parse => "parse",
+            Pass::Resolve => "resolve",
+            Pass::Kindck => "kindck",
+            Pass::Typeck => "typeck",
+            Pass::Bridge => "bridge",
+            Pass::Lower => "lower",
+            Pass::Flatten => "flatten",
+            Pass::Hoist => "hoist",
+            Pass::Eval => "eval",
+            Pass::Emit => "emit",
+            Pass::Compile => "compile",
+        }
+    }
+}
+
+/// Some measure of the size of a program, as reported by
+/// [`Driver::report_stat`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Stat {
+    Tokens,
+    Decls,
+    StmtsBeforeEval,
+    StmtsAfterEval,
+
+    /// The number of distinct types in the MIR.
+    Types,
+}
+
+impl Stat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stat::Tokens => "tokens",
+            Stat::Decls => "declarations",
+            Stat::StmtsBeforeEval => "statements before eval",
+            Stat::StmtsAfterEval => "statements after eval",
+            Stat::Types => "types",
+        }
+    }
 }
@@ -29,6 +29,18 @@ impl BindIdGenerator {
     }
 }
 
+/// A function implemented in C rather than in the program, declared with
+/// `extern fun`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Extern {
+    /// The name of the function in C.
+    pub symbol: String,
+
+    /// The header declaring the function. If there is none, a prototype is
+    /// generated from the type of the function instead.
+    pub header: Option<String>,
+}
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Decls<Name = String> {
     pub values: Vec<ValueDef<Name>>,
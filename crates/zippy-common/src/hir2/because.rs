@@ -10,4 +10,6 @@ pub enum Because {
     /// This type constraint is due to this expression being called with the
     /// spanned argument.
     Called(Span),
+    /// This type constraint is due to the spanned expression being negated.
+    Negated(Span),
 }
@@ -88,6 +88,11 @@ impl Context {
         self.names.insert(*name, schema);
     }
 
+    /// Get an iterator over every name in this context and its type.
+    pub fn iter(&self) -> impl Iterator<Item = (&Name, &TypeOrSchema)> {
+        self.names.iter()
+    }
+
     /// Get an iterator over all of the polymorphic names in this context.
     pub fn polymorphic_names(&self) -> impl Iterator<Item = Name> + '_ {
         self.names.iter().filter_map(|(name, ty)| match ty {
@@ -150,10 +150,17 @@ impl<'a> Prettier<'a> {
 
     fn pretty_range(&mut self, ty: &Type) -> String {
         match ty {
-            Type::Range(lo, hi) => {
+            Type::Range(lo, hi, None) => {
                 format!("{} upto {}", self.pretty_name(lo), self.pretty_name(hi))
             }
 
+            Type::Range(lo, hi, Some(step)) => format!(
+                "{} upto {} by {}",
+                self.pretty_name(lo),
+                self.pretty_name(hi),
+                self.pretty_name(step)
+            ),
+
             Type::Var(_, var) => {
                 if let Some(ty) = self.subst.get(var) {
                     self.pretty_range(ty)
@@ -200,6 +207,7 @@ impl<'a> Prettier<'a> {
             }
 
             Type::Number => "<number>".into(),
+            Type::Text => "text".into(),
             Type::Type => "type".into(),
 
             Type::Invalid => "<error>".into(),
@@ -214,8 +222,12 @@ impl<'a> Prettier<'a> {
         } else {
             match name.name(self.db) {
                 NamePart::Source(name) => name.clone(),
-                NamePart::Scope(_) => todo!(),
-                NamePart::Spanned(_) => todo!(),
+                NamePart::File(_) => "<file>".into(),
+                NamePart::Scope(_) => "<scope>".into(),
+
+                // Lifted range bounds are named by their span, and we don't
+                // have access to the source text here.
+                NamePart::Spanned(_) => "<expr>".into(),
             }
         }
     }
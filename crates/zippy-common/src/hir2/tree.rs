@@ -1,4 +1,5 @@
 use super::{CoercionId, Type};
+use crate::hir::Extern;
 use crate::message::Span;
 use crate::names2::Name;
 use crate::Number;
@@ -12,6 +13,7 @@ pub struct Decls {
 pub struct ValueDef {
     pub span: Span,
     pub pat: Pat,
+    pub implicits: Vec<Name>,
     pub body: Expr,
 }
 
@@ -26,6 +28,7 @@ pub struct Expr {
 pub enum ExprNode {
     Name(Name),
     Num(Number),
+    Str(String),
 
     Lam(Pat, Box<Expr>),
     App(Box<Expr>, Box<Expr>),
@@ -35,7 +38,9 @@ pub enum ExprNode {
     Coerce(Box<Expr>, CoercionId),
 
     Tuple(Box<Expr>, Box<Expr>),
+    Neg(Box<Expr>),
 
+    Extern(Extern),
     Hole,
     Invalid,
 }
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::names2::Name;
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct UniVar(pub(super) usize);
 
 /// Determines the mutability of a type variable. Mutable type variables can be "assigned" a substitution, while an
@@ -20,8 +20,9 @@ pub enum Type {
     Name(Name),
 
     /// A range is represented as a pair of names, where the names are the
-    /// actual expression.
-    Range(Name, Name),
+    /// actual expression, along with the name of its resolution if it has
+    /// one.
+    Range(Name, Name, Option<Name>),
     Fun(Box<Type>, Box<Type>),
 
     Product(Box<Type>, Box<Type>),
@@ -29,6 +30,7 @@ pub enum Type {
     Instantiated(Box<Type>, HashMap<Name, Type>),
     Var(Mutability, UniVar),
     Number,
+    Text,
     Type,
 
     Invalid,
@@ -47,7 +49,7 @@ impl Type {
         match self {
             Type::Name(name) => Type::Name(*name),
 
-            Type::Range(lo, hi) => Type::Range(*lo, *hi),
+            Type::Range(lo, hi, step) => Type::Range(*lo, *hi, *step),
 
             Type::Fun(t, u) => {
                 let t = Box::new(t.make_mutability(mutability));
@@ -69,6 +71,7 @@ impl Type {
             Type::Var(_, var) => Type::Var(mutability, *var),
 
             Type::Number => Type::Number,
+            Type::Text => Type::Text,
             Type::Type => Type::Type,
             Type::Invalid => Type::Invalid,
         }
@@ -107,8 +110,9 @@ pub fn instantiate(mapping: &HashMap<Name, Type>, ty: &Type) -> Type {
             Type::Instantiated(ty, prev_mapping.clone())
         }
 
-        Type::Range(lo, hi) => Type::Range(*lo, *hi),
+        Type::Range(lo, hi, step) => Type::Range(*lo, *hi, *step),
         Type::Number => Type::Number,
+        Type::Text => Type::Text,
         Type::Type => Type::Type,
         Type::Var(mutable, var) => Type::Var(*mutable, *var),
         Type::Invalid => Type::Invalid,
@@ -11,7 +11,7 @@ pub mod thir;
 pub use malachite::Rational as Number;
 use salsa::DbWithJar;
 
-pub use self::driver::{Driver, EvalAmount, IrOutput};
+pub use self::driver::{Driver, EvalAmount, IrOutput, Pass, Stat};
 
 mod driver;
 
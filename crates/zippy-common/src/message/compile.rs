@@ -1,6 +1,18 @@
-use super::{Diagnostic, Label, MessageAdder};
+use super::{Diagnostic, Label, MessageAdder, Span};
 
-const UNCONSTRAINED_RANGE: &str = "EC00";
+codes! {
+    UNCONSTRAINED_RANGE = "EC00";
+    TEST_NOT_A_NUMBER = "EC01";
+    RANGE_TOO_LARGE = "EC02";
+    EXPORT_NOT_A_FUNCTION = "EC03";
+    EXTERN_UNSUPPORTED_TYPE = "EC04";
+    NOT_A_MULTIPLE = "EC05";
+    INVALID_RESOLUTION = "EC06";
+    DUPLICATE_SYMBOL = "EC07";
+    RESERVED_SYMBOL = "EC08";
+    EXTERN_SIGNATURE_MISMATCH = "EC09";
+    INEXACT_RESCALE = "EC10";
+}
 
 impl<'a> MessageAdder<'a> {
     pub fn compile_unconstrained_range(&mut self) {
@@ -19,4 +31,158 @@ impl<'a> MessageAdder<'a> {
                 .with_notes(notes),
         );
     }
+
+    pub fn compile_test_not_a_number(&mut self) {
+        let labels = vec![Label::primary(self.at)];
+        let notes = vec![
+            "note: a test passes if it evaluates to zero, so it must evaluate to a number".into(),
+        ];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(TEST_NOT_A_NUMBER)
+                .with_message("test does not evaluate to a number")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn compile_range_too_large(&mut self, lo: &str, hi: &str) {
+        let labels = vec![Label::primary(self.at)];
+        let notes = vec![format!(
+            "note: the range from {lo} to {hi} does not fit in any integer type on this target"
+        )];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(RANGE_TOO_LARGE)
+                .with_message("range too large")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn compile_export_not_a_function(&mut self) {
+        let labels = vec![Label::primary(self.at).with_message("this is not a function")];
+        let notes = vec!["note: an exported function needs at least one parameter".into()];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(EXPORT_NOT_A_FUNCTION)
+                .with_message("cannot export a value which is not a function")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn compile_extern_unsupported_type(&mut self, ty: impl Into<String>) {
+        let labels =
+            vec![Label::primary(self.at).with_message(format!("this has type {}", ty.into()))];
+        let notes = vec![
+            "note: the parameters and result of an external function must be ranges or text".into(),
+        ];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(EXTERN_UNSUPPORTED_TYPE)
+                .with_message("external function with a type that has no C equivalent")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn compile_not_a_multiple(&mut self, number: &str, step: &str) {
+        let labels = vec![Label::primary(self.at)];
+        let notes = vec![
+            format!("note: {number} is not a multiple of the resolution {step}"),
+            "note: a range without `by` has a resolution of 1".into(),
+        ];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(NOT_A_MULTIPLE)
+                .with_message("number cannot be represented exactly")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn compile_invalid_resolution(&mut self, step: &str) {
+        let labels = vec![Label::primary(self.at).with_message(format!("this is {step}"))];
+        let notes = vec!["note: the resolution of a range must be greater than zero".into()];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(INVALID_RESOLUTION)
+                .with_message("invalid resolution")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn compile_inexact_rescale(&mut self, from: &str, to: &str) {
+        let labels = vec![Label::primary(self.at)];
+        let notes = vec![
+            format!("note: the resolution {from} is not a multiple of the resolution {to}"),
+            "note: a range without `by` has a resolution of 1".into(),
+        ];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(INEXACT_RESCALE)
+                .with_message("value cannot be converted to this resolution exactly")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn compile_duplicate_symbol(&mut self, symbol: &str, previous: Span) {
+        let labels = vec![
+            Label::primary(self.at),
+            Label::secondary(previous).with_message(format!("`{symbol}` is also used here")),
+        ];
+        let notes = vec!["note: every function in C must have a name of its own".into()];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(DUPLICATE_SYMBOL)
+                .with_message(format!("the C name `{symbol}` is used more than once"))
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn compile_reserved_symbol(&mut self, symbol: &str, reason: impl Into<String>) {
+        let labels = vec![Label::primary(self.at)];
+        let notes = vec![reason.into()];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(RESERVED_SYMBOL)
+                .with_message(format!("`{symbol}` cannot be used as a name in C"))
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn compile_extern_signature_mismatch(
+        &mut self,
+        signature: &str,
+        previous: Span,
+        previous_signature: &str,
+    ) {
+        let labels = vec![
+            Label::primary(self.at).with_message(format!("this is `{signature}`")),
+            Label::secondary(previous).with_message(format!("but this is `{previous_signature}`")),
+        ];
+        let notes = vec!["note: a C function has only one signature".into()];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(EXTERN_SIGNATURE_MISMATCH)
+                .with_message("external function declared with different signatures")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
 }
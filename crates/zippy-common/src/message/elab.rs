@@ -1,9 +1,11 @@
 use super::{Diagnostic, Label, MessageAdder, Messages, Span};
 
-const OUTSIDE_RANGE: &str = "EE00";
-const CLOSURE: &str = "EE01";
+codes! {
+    OUTSIDE_RANGE = "EE00";
+    CLOSURE = "EE01";
 
-const REPORT_HOLE: &str = "HE00";
+    REPORT_HOLE = "HE00";
+}
 
 impl Messages {
     pub fn elab_closure_not_permitted(&mut self, free: impl Iterator<Item = Span>) {
@@ -0,0 +1,95 @@
+//! Long-form explanations of message codes, each with an example of code
+//! causing the message and an example of how to fix it. Every code used by
+//! any message has an explanation; this is checked when the crate is compiled.
+
+use super::{compile, elab, kick, lex, parse, resolve, test, tyck};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Explanation {
+    pub code: &'static str,
+    pub text: &'static str,
+}
+
+macro_rules! explanations {
+    ($($code:literal,)*) => {
+        &[$(Explanation {
+            code: $code,
+            text: include_str!(concat!("explain/", $code, ".md")),
+        }),*]
+    };
+}
+
+const EXPLANATIONS: &[Explanation] = explanations![
+    "EC00", "EC01", "EC02", "EC03", "EC04", "EC05", "EC06", "EC07", "EC08", "EC09", "EC10", "EE00",
+    "EE01", "EK00", "EK01", "EK02", "EL00", "EL01", "EL02", "EL03", "EL04", "EL05", "EP00", "EP01",
+    "EP02", "EP03", "EP05", "EP06", "EP07", "EP08", "EP09", "EP10", "EP11", "EP12", "EP13", "EP14",
+    "EP15", "EP16", "EP17", "EP18", "ER00", "ER01", "ER02", "ER03", "ER04", "ET00", "ET01", "ET02",
+    "ET03", "ET04", "ET05", "ET06", "ET07", "ET08", "ET09", "ET10", "EX00", "HE00",
+];
+
+/// The codes used by the messages of every module.
+const CODES: &[&[&str]] = &[
+    compile::CODES,
+    elab::CODES,
+    kick::CODES,
+    lex::CODES,
+    parse::CODES,
+    resolve::CODES,
+    test::CODES,
+    tyck::CODES,
+];
+
+const _: () = {
+    let mut i = 0;
+    while i < CODES.len() {
+        let mut j = 0;
+        while j < CODES[i].len() {
+            assert!(
+                is_explained(CODES[i][j]),
+                "message code without explanation"
+            );
+            j += 1;
+        }
+
+        i += 1;
+    }
+};
+
+/// Get the explanation of a message code, ignoring case.
+pub fn explain(code: &str) -> Option<&'static Explanation> {
+    EXPLANATIONS
+        .iter()
+        .find(|explanation| explanation.code.eq_ignore_ascii_case(code))
+}
+
+const fn is_explained(code: &str) -> bool {
+    let mut i = 0;
+    while i < EXPLANATIONS.len() {
+        if same(EXPLANATIONS[i].code, code) {
+            return true;
+        }
+
+        i += 1;
+    }
+
+    false
+}
+
+/// String comparison usable in constants.
+const fn same(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+
+        i += 1;
+    }
+
+    true
+}
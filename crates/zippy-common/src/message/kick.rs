@@ -1,8 +1,10 @@
 use super::{Diagnostic, Label, MessageAdder};
 
-const RECURSIVE_KINDS: &str = "EK00";
-const INCOMPATIBLE_KINDS: &str = "EK01";
-const NOT_A_KIND: &str = "EK02";
+codes! {
+    RECURSIVE_KINDS = "EK00";
+    INCOMPATIBLE_KINDS = "EK01";
+    NOT_A_KIND = "EK02";
+}
 
 impl MessageAdder<'_> {
     pub fn kick_recursive_kind(&mut self) {
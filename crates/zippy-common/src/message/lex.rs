@@ -1,6 +1,13 @@
 use super::{Diagnostic, Label, MessageAdder};
 
-const INVALID_CHARACTER: &str = "EL00";
+codes! {
+    INVALID_CHARACTER = "EL00";
+    UNTERMINATED_LITERAL = "EL01";
+    INVALID_ESCAPE = "EL02";
+    INVALID_CHAR_LITERAL = "EL03";
+    MISSING_DIGITS = "EL04";
+    INVALID_DIGIT = "EL05";
+}
 
 impl<'a> MessageAdder<'a> {
     pub fn lex_invalid(&mut self) {
@@ -13,4 +20,85 @@ impl<'a> MessageAdder<'a> {
                 .with_labels(labels),
         );
     }
+
+    pub fn lex_unterminated_literal(&mut self, quote: char) {
+        let labels =
+            vec![Label::primary(self.at).with_message(format!("expected a closing `{quote}`"))];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(UNTERMINATED_LITERAL)
+                .with_message("unterminated literal")
+                .with_labels(labels),
+        );
+    }
+
+    pub fn lex_invalid_escape(&mut self) {
+        let labels = vec![Label::primary(self.at)];
+        let notes = vec![
+            "note: the valid escape sequences are `\\n`, `\\r`, `\\t`, `\\\\`, `\\\"`, `\\'` and `\\u{...}`"
+                .into(),
+        ];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(INVALID_ESCAPE)
+                .with_message("invalid escape sequence")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn lex_invalid_char_literal(&mut self) {
+        let labels = vec![Label::primary(self.at)];
+        let notes = vec!["note: use double quotes for a string".into()];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(INVALID_CHAR_LITERAL)
+                .with_message("a character literal must contain exactly one character")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn lex_missing_digits(&mut self, prefix: &str) {
+        let message = if prefix.is_empty() {
+            "expected digits".into()
+        } else {
+            format!("expected digits after `{prefix}`")
+        };
+
+        let labels = vec![Label::primary(self.at).with_message(message)];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(MISSING_DIGITS)
+                .with_message("number literal without digits")
+                .with_labels(labels),
+        );
+    }
+
+    pub fn lex_invalid_digit(&mut self, digit: char, radix: u32) {
+        let base = match radix {
+            2 => "binary",
+            8 => "octal",
+            16 => "hexadecimal",
+            _ => "decimal",
+        };
+
+        let labels =
+            vec![Label::primary(self.at).with_message(format!("`{digit}` is not a {base} digit"))];
+        let notes = vec![
+            "note: hexadecimal, octal and binary numbers start with `0x`, `0o` and `0b`".into(),
+        ];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(INVALID_DIGIT)
+                .with_message(format!("invalid digit in {base} number"))
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
 }
@@ -1,4 +1,15 @@
+/// Declare the codes of the messages in a module, along with a list of every
+/// one of them, which is used to check that every code has an explanation.
+macro_rules! codes {
+    ($($name:ident = $code:literal;)*) => {
+        $(const $name: &str = $code;)*
+
+        pub(super) const CODES: &[&str] = &[$($name),*];
+    };
+}
+
 mod diagnostic;
+mod explain;
 mod source;
 
 mod compile;
@@ -7,10 +18,12 @@ mod kick;
 mod lex;
 mod parse;
 mod resolve;
+mod test;
 mod tyck;
 
 pub use diagnostic::{Diagnostic, Label, LabelStyle, Severity};
-pub use source::{File, Span};
+pub use explain::{explain, Explanation};
+pub use source::{File, SourceMap, Span};
 
 #[derive(Debug, Default)]
 pub struct Messages {
@@ -1,16 +1,25 @@
 use super::{Diagnostic, Label, MessageAdder};
 
-const BASE_EXPR: &str = "EP00";
-const DECLARATION: &str = "EP01";
-const DISALLOWED_IMPLICITS: &str = "EP08";
-const EXPR: &str = "EP10";
-const GENERIC_LAMBDA: &str = "EP09";
-const NOT_A_PAT: &str = "EP02";
-const NOT_A_TYPE: &str = "EP03";
-const NOT_A_TYPE_NAME: &str = "EP07";
-const TYPE_IMPLICITS: &str = "EP11";
-const UNCLOSED_GROUP: &str = "EP05";
-const UNCLOSED_IMPLICITS: &str = "EP06";
+codes! {
+    BASE_EXPR = "EP00";
+    DECLARATION = "EP01";
+    DISALLOWED_IMPLICITS = "EP08";
+    EXPR = "EP10";
+    EXPECTED_TEST_NAME = "EP13";
+    EXPORT_NOT_A_FUN = "EP14";
+    EXTERN_NOT_A_FUN = "EP15";
+    EXTERN_BODY = "EP16";
+    EXTERN_IMPLICITS = "EP17";
+    GENERIC_LAMBDA = "EP09";
+    NOT_A_MODULE_NAME = "EP12";
+    NOT_A_PAT = "EP02";
+    NOT_A_TYPE = "EP03";
+    NOT_A_TYPE_NAME = "EP07";
+    TEST_WITHOUT_BODY = "EP18";
+    TYPE_IMPLICITS = "EP11";
+    UNCLOSED_GROUP = "EP05";
+    UNCLOSED_IMPLICITS = "EP06";
+}
 
 impl<'a> MessageAdder<'a> {
     pub fn parse_expected_base_expr(&mut self) {
@@ -36,6 +45,82 @@ impl<'a> MessageAdder<'a> {
         );
     }
 
+    pub fn parse_expected_test_name(&mut self) {
+        let labels = vec![Label::primary(self.at).with_message("expected a string")];
+        let notes = vec!["note: tests are written like `test \"name\" = expr`".into()];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(EXPECTED_TEST_NAME)
+                .with_message("expected the name of the test")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn parse_test_without_body(&mut self) {
+        let labels = vec![Label::primary(self.at).with_message("expected `=` and an expression")];
+        let notes = vec!["note: tests are written like `test \"name\" = expr`".into()];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(TEST_WITHOUT_BODY)
+                .with_message("test without a body")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn parse_expected_exported_function(&mut self) {
+        let labels = vec![Label::primary(self.at).with_message("expected `fun`")];
+        let notes = vec!["note: only functions can be exported".into()];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(EXPORT_NOT_A_FUN)
+                .with_message("expected a function to export")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn parse_expected_extern_function(&mut self) {
+        let labels = vec![Label::primary(self.at).with_message("expected `fun`")];
+        let notes = vec!["note: only functions can be declared `extern`".into()];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(EXTERN_NOT_A_FUN)
+                .with_message("expected an external function")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn parse_extern_with_body(&mut self) {
+        let labels = vec![Label::primary(self.at).with_message("remove this body")];
+        let notes = vec!["note: external functions are implemented in C".into()];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(EXTERN_BODY)
+                .with_message("external functions cannot have a body")
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn parse_extern_implicits(&mut self) {
+        let labels = vec![Label::primary(self.at)];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(EXTERN_IMPLICITS)
+                .with_message("external functions cannot have implicit parameters")
+                .with_labels(labels),
+        );
+    }
+
     pub fn parse_disallowed_implicits(&mut self) {
         let labels = vec![Label::primary(self.at)];
         let notes = vec!["note: implicit list only allowed right after function name".into()];
@@ -85,6 +170,17 @@ impl<'a> MessageAdder<'a> {
         );
     }
 
+    pub fn parse_not_a_module_name(&mut self) {
+        let labels = vec![Label::primary(self.at).with_message("expected the name of a file")];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(NOT_A_MODULE_NAME)
+                .with_message("expected a module name")
+                .with_labels(labels),
+        );
+    }
+
     pub fn parse_not_a_type(&mut self) {
         let labels = vec![Label::primary(self.at)];
 
@@ -1,8 +1,12 @@
 use super::{Diagnostic, Label, MessageAdder, Messages, Span};
 
-const REDECLARATION: &str = "ER00";
-const UNKNOWN_NAME: &str = "ER01";
-const NO_ENTRY_POINT: &str = "ER02";
+codes! {
+    REDECLARATION = "ER00";
+    UNKNOWN_NAME = "ER01";
+    NO_ENTRY_POINT = "ER02";
+    UNKNOWN_MODULE = "ER03";
+    AMBIGUOUS_NAME = "ER04";
+}
 
 impl Messages {
     pub fn resolve_no_entry_point(&mut self) {
@@ -39,4 +43,35 @@ impl<'a> MessageAdder<'a> {
                 .with_labels(labels),
         );
     }
+
+    pub fn resolve_ambiguous_name(&mut self, name: &str, first: &str, second: &str) {
+        let labels = vec![Label::primary(self.at)];
+        let notes = vec![
+            format!("note: both '{first}' and '{second}' declare '{name}'"),
+            "note: rename one of them, or define it in this file to shadow both".into(),
+        ];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(AMBIGUOUS_NAME)
+                .with_message(format!("name '{name}' is ambiguous"))
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+
+    pub fn resolve_unknown_module(&mut self, name: &str) {
+        let labels = vec![Label::primary(self.at)];
+        let notes = vec![format!(
+            "note: looked for '{name}.z' next to the importing file"
+        )];
+
+        self.add(
+            Diagnostic::error()
+                .with_code(UNKNOWN_MODULE)
+                .with_message(format!("could not find module '{name}'"))
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
 }
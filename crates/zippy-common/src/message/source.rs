@@ -52,3 +52,33 @@ impl Sum for Span {
         res
     }
 }
+
+/// The names and line breaks of some source files, used to find the line a
+/// span starts on.
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    files: Vec<(String, Vec<usize>)>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self { files: Vec::new() }
+    }
+
+    /// Add a file to the map. Files must be added in the order of their ids.
+    pub fn add(&mut self, name: String, source: &str) -> File {
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(at, _)| at + 1))
+            .collect();
+
+        self.files.push((name, starts));
+        self.files.len() - 1
+    }
+
+    /// Get the name of the file and the one-based line the span starts on.
+    pub fn locate(&self, span: Span) -> Option<(&str, usize)> {
+        let (name, starts) = self.files.get(span.file)?;
+        let line = starts.partition_point(|start| *start <= span.start);
+        Some((name, line))
+    }
+}
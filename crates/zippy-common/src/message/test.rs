@@ -0,0 +1,27 @@
+use super::{Diagnostic, Label, MessageAdder};
+
+codes! {
+    TEST_FAILED = "EX00";
+}
+
+impl<'a> MessageAdder<'a> {
+    pub fn test_failed(&mut self, description: &str, evaluated: bool) {
+        let labels = vec![Label::primary(self.at)];
+
+        let mut notes = vec![String::from(
+            "note: a test passes if it evaluates to zero without aborting",
+        )];
+
+        if evaluated {
+            notes.push("note: this test was evaluated at compile time".into());
+        }
+
+        self.add(
+            Diagnostic::error()
+                .with_code(TEST_FAILED)
+                .with_message(format!("test '{description}' failed"))
+                .with_labels(labels)
+                .with_notes(notes),
+        );
+    }
+}
@@ -1,17 +1,19 @@
 use super::{Diagnostic, Label, MessageAdder};
 use crate::Number;
 
-const AMBIGUOUS: &str = "ET05";
-const INCOMPATIBLE_TYPES: &str = "ET00";
-const INSTANTIATE_NOT_GENERIC: &str = "ET09";
-const INSTANTIATE_NON_NAME: &str = "ET07";
-const INSTANTIATE_WRONG_ARITY: &str = "ET08";
-const NARROW_RANGE: &str = "ET01";
-const NO_PROGRESS: &str = "ET02";
-const NOT_A_FUN: &str = "ET03";
-const NOT_AN_INT: &str = "ET04";
-const RECURSIVE: &str = "ET06";
-const TUPLE_TYPE: &str = "ET10";
+codes! {
+    AMBIGUOUS = "ET05";
+    INCOMPATIBLE_TYPES = "ET00";
+    INSTANTIATE_NOT_GENERIC = "ET09";
+    INSTANTIATE_NON_NAME = "ET07";
+    INSTANTIATE_WRONG_ARITY = "ET08";
+    NARROW_RANGE = "ET01";
+    NO_PROGRESS = "ET02";
+    NOT_A_FUN = "ET03";
+    NOT_AN_INT = "ET04";
+    RECURSIVE = "ET06";
+    TUPLE_TYPE = "ET10";
+}
 
 impl<'a> MessageAdder<'a> {
     pub fn tyck_ambiguous(&mut self) {
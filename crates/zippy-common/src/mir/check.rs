@@ -173,6 +173,13 @@ impl<'a> MirChecker<'a> {
                 let of_type = self.context.get(of);
                 self.check_type(expr.span, *from, of_type);
             }
+
+            StmtNode::Neg { name, of } => {
+                let ty = self.context.get(name);
+                let of_type = self.context.get(of);
+                self.check_numeric(expr.span, ty);
+                self.check_numeric(expr.span, of_type);
+            }
         }
     }
 
@@ -186,6 +193,11 @@ impl<'a> MirChecker<'a> {
                 _ => unreachable!(),
             },
 
+            ValueNode::Text(_) => match self.types.get(&actual) {
+                Type::Text | Type::Invalid => {}
+                _ => unreachable!(),
+            },
+
             ValueNode::Name(name) => {
                 let actual2 = self.context.get(name);
                 self.check_type(value.span, actual, actual2);
@@ -195,6 +207,18 @@ impl<'a> MirChecker<'a> {
         }
     }
 
+    fn check_numeric(&mut self, at: Span, ty: TypeId) {
+        match self.types.get(&ty) {
+            Type::Range(..) | Type::Number | Type::Invalid => {}
+            _ => {
+                self.error = true;
+                let prettier = Prettier::new(self.names, self.types);
+                eprintln!("type mismatch at {:?}", at);
+                eprintln!("expected a number, got {}", prettier.pretty_type(&ty));
+            }
+        }
+    }
+
     fn check_type(&mut self, at: Span, expected: TypeId, actual: TypeId) {
         match (self.types.get(&expected), self.types.get(&actual)) {
             (Type::Invalid, _) | (_, Type::Invalid) => {}
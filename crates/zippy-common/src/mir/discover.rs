@@ -8,15 +8,16 @@ use super::{
 };
 use crate::names::Name;
 
-/// Get a list of all of the names reachable from the entry point, as well as
-/// all of the names which are directly used by types.
+/// Get a list of all of the names reachable from the given roots (usually just
+/// the entry point), as well as all of the names which are directly used by
+/// types.
 pub fn discover(
     types: &Types,
     context: &Context,
-    entry: Option<Name>,
+    roots: &[Name],
     decls: &Decls,
 ) -> (Vec<Name>, HashSet<Name>) {
-    let mut discoverer = MirDiscoverer::new(types, context, entry);
+    let mut discoverer = MirDiscoverer::new(types, context, roots);
     discoverer.discover_decls(decls);
     (discoverer.names, discoverer.in_types)
 }
@@ -34,15 +35,12 @@ struct MirDiscoverer<'a> {
 }
 
 impl<'a> MirDiscoverer<'a> {
-    pub fn new(types: &'a Types, context: &'a Context, entry: Option<Name>) -> Self {
+    pub fn new(types: &'a Types, context: &'a Context, roots: &[Name]) -> Self {
         Self {
             types,
             context,
 
-            worklist: match entry {
-                Some(entry) => vec![entry],
-                None => Vec::new(),
-            },
+            worklist: roots.to_vec(),
             names: Vec::new(),
 
             in_types: HashSet::new(),
@@ -113,7 +111,7 @@ impl<'a> MirDiscoverer<'a> {
                 values.iter().for_each(|value| self.discover_value(value));
             }
 
-            StmtNode::Proj { of, .. } => {
+            StmtNode::Proj { of, .. } | StmtNode::Neg { of, .. } => {
                 self.worklist.push(*of);
             }
 
@@ -128,7 +126,7 @@ impl<'a> MirDiscoverer<'a> {
         self.discover_type(&value.ty);
 
         match &value.node {
-            StaticValueNode::Num(_) => {}
+            StaticValueNode::Num(_) | StaticValueNode::Text(_) => {}
             StaticValueNode::LateInit(block) => self.discover_block(block),
         }
     }
@@ -137,7 +135,7 @@ impl<'a> MirDiscoverer<'a> {
         self.discover_type(&value.ty);
 
         match &value.node {
-            ValueNode::Num(_) | ValueNode::Invalid => {}
+            ValueNode::Num(_) | ValueNode::Text(_) | ValueNode::Invalid => {}
             ValueNode::Name(name) => self.worklist.push(*name),
         }
     }
@@ -160,11 +158,12 @@ impl<'a> MirDiscoverer<'a> {
                 }
             }
 
-            Type::Range(lo, hi) => {
+            Type::Range(lo, hi, step) => {
                 self.in_types.extend([*lo, *hi]);
+                self.in_types.extend(*step);
             }
 
-            Type::Number => {}
+            Type::Number | Type::Text => {}
 
             Type::Invalid => {}
         }
@@ -0,0 +1,181 @@
+//! Renders the control flow of a program as a Graphviz graph. Every block is a
+//! node listing its statements, with an edge for every jump to a join point.
+//! Every definition and function gets its own cluster, and functions defined
+//! within another one are nested inside its cluster.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::pretty::Prettier;
+use super::{Block, Branch, BranchNode, Decls, Statement, StaticValueNode, StmtNode, Types};
+use crate::names::{Name, Names};
+
+pub fn dot(names: &Names, types: &Types, decls: &Decls) -> String {
+    let mut grapher = Grapher {
+        names,
+        types,
+        res: String::from("digraph mir {\n\tnode [shape=box];\n"),
+        blocks: 0,
+        clusters: 0,
+        joins: HashMap::new(),
+        jumps: Vec::new(),
+    };
+
+    for def in decls.defs.iter() {
+        let label = format!("let {}", grapher.name(&def.name));
+        grapher.cluster(label, &def.bind);
+    }
+
+    let mut values: Vec<_> = decls
+        .values
+        .iter()
+        .map(|(name, value)| (grapher.name(name), value))
+        .collect();
+
+    values.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (name, value) in values {
+        match &value.node {
+            StaticValueNode::Num(n) => {
+                grapher.node(vec![format!("let {name} = {n}")]);
+            }
+
+            StaticValueNode::Text(text) => {
+                grapher.node(vec![format!("let {name} = {text:?}")]);
+            }
+
+            StaticValueNode::LateInit(block) => grapher.cluster(format!("let {name}"), block),
+        }
+    }
+
+    let mut functions: Vec<_> = decls
+        .functions
+        .iter()
+        .map(|(name, (params, body))| (grapher.fun(name, params), body))
+        .collect();
+
+    functions.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (label, body) in functions {
+        grapher.cluster(label, body);
+    }
+
+    for (from, to) in std::mem::take(&mut grapher.jumps) {
+        if let Some(to) = grapher.joins.get(&to) {
+            writeln!(grapher.res, "\tb{from} -> b{to};").unwrap();
+        }
+    }
+
+    grapher.res.push_str("}\n");
+    grapher.res
+}
+
+struct Grapher<'a> {
+    names: &'a Names,
+    types: &'a Types,
+    res: String,
+
+    /// The number of blocks and clusters so far, which are used to name them.
+    blocks: usize,
+    clusters: usize,
+
+    /// The block of every join point.
+    joins: HashMap<Name, usize>,
+
+    /// Every jump, from a block to a join point. These are drawn once every
+    /// join point has been given a block.
+    jumps: Vec<(usize, Name)>,
+}
+
+impl Grapher<'_> {
+    fn cluster(&mut self, label: String, block: &Block) {
+        writeln!(self.res, "subgraph cluster_{} {{", self.clusters).unwrap();
+        writeln!(self.res, "\tlabel=\"{}\";", escape(&label)).unwrap();
+        self.clusters += 1;
+
+        self.block(block);
+        self.res.push_str("}\n");
+    }
+
+    /// Add a node for the block, followed by the blocks of the join points and
+    /// functions it defines. Returns the number of the block.
+    fn block(&mut self, block: &Block) -> usize {
+        let mut lines = Vec::with_capacity(block.stmts.len() + 1);
+        let mut joins = Vec::new();
+        let mut functions = Vec::new();
+
+        for stmt in block.stmts.iter() {
+            match &stmt.node {
+                StmtNode::Join { name, param, body } => {
+                    lines.push(format!("join {}({})", self.name(name), self.name(param)));
+                    joins.push((*name, body));
+                }
+
+                StmtNode::Function { name, params, body } => {
+                    let label = self.fun(name, params);
+                    lines.push(label.clone());
+                    functions.push((label, body));
+                }
+
+                _ => lines.push(self.stmt(stmt)),
+            }
+        }
+
+        lines.push(self.branch(&block.branch));
+
+        let id = self.node(lines);
+        if let BranchNode::Jump(to, _) = &block.branch.node {
+            self.jumps.push((id, *to));
+        }
+
+        for (name, body) in joins {
+            let body = self.block(body);
+            self.joins.insert(name, body);
+        }
+
+        for (label, body) in functions {
+            self.cluster(label, body);
+        }
+
+        id
+    }
+
+    /// Add a node with the given lines of text, returning its number.
+    fn node(&mut self, lines: Vec<String>) -> usize {
+        let id = self.blocks;
+        self.blocks += 1;
+
+        let label: String = lines
+            .iter()
+            .map(|line| escape(line).replace('\n', "\\l") + "\\l")
+            .collect();
+
+        writeln!(self.res, "\tb{id} [label=\"{label}\"];").unwrap();
+        id
+    }
+
+    fn fun(&self, name: &Name, params: &[Name]) -> String {
+        let params: Vec<_> = params.iter().map(|param| self.name(param)).collect();
+        format!("fun {}({})", self.name(name), params.join(", "))
+    }
+
+    fn name(&self, name: &Name) -> String {
+        let prettier = Prettier::new(self.names, self.types);
+        prettier.pretty_name(name)
+    }
+
+    fn stmt(&self, stmt: &Statement) -> String {
+        let prettier = Prettier::new(self.names, self.types);
+        prettier.pretty_stmt(stmt)
+    }
+
+    fn branch(&self, branch: &Branch) -> String {
+        let prettier = Prettier::new(self.names, self.types);
+        prettier.pretty_branch(branch)
+    }
+}
+
+/// Escape a string for use in a quoted Graphviz label.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -1,3 +1,4 @@
+pub mod dot;
 pub mod pretty;
 
 use std::collections::HashMap;
@@ -22,13 +23,18 @@ pub struct TypeId(usize);
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Type {
-    Range(Name, Name),
+    /// A range from one bound to another. Ranges with a resolution hold
+    /// fractions, which are stored as integer multiples of the resolution.
+    Range(Name, Name, Option<Name>),
     Fun(Vec<TypeId>, Vec<TypeId>),
     Product(Vec<TypeId>),
 
     /// Arbitrary-precision numeric type, used by some expressions in range
     /// bounds.
     Number,
+
+    /// Immutable text, from string literals.
+    Text,
     Invalid,
 }
 
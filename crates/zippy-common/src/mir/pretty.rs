@@ -54,6 +54,22 @@ impl<'a> Prettier<'a> {
         String::from_utf8(res).unwrap()
     }
 
+    #[must_use]
+    pub fn pretty_stmt(&'a self, stmt: &Statement) -> String {
+        let doc = self.doc_expr(None, stmt);
+        let mut res = Vec::new();
+        doc.render(self.width, &mut res).unwrap();
+        String::from_utf8(res).unwrap()
+    }
+
+    #[must_use]
+    pub fn pretty_branch(&'a self, branch: &Branch) -> String {
+        let doc = self.doc_branch(None, branch);
+        let mut res = Vec::new();
+        doc.render(self.width, &mut res).unwrap();
+        String::from_utf8(res).unwrap()
+    }
+
     #[must_use]
     pub fn pretty_name(&'a self, name: &Name) -> String {
         let doc = self.doc_name(None, name);
@@ -96,6 +112,14 @@ impl<'a> Prettier<'a> {
                     self.doc_fun(None, "fun", name, param)
                         .append(self.doc_block(Some(name), body))
                         .nest(2)
+                }))
+                .chain(decls.externs.iter().map(|(name, external)| {
+                    self.allocator
+                        .text("extern")
+                        .append(self.allocator.space())
+                        .append(self.doc_name(None, name))
+                        .append(" = ")
+                        .append(self.allocator.text(external.symbol.clone()))
                 })),
             self.allocator.hardline(),
         )
@@ -113,10 +137,19 @@ impl<'a> Prettier<'a> {
 
     fn doc_type(&'a self, within: Option<&Name>, ty: &TypeId) -> DocBuilder<Arena<'a>> {
         match self.types.get(ty) {
-            Type::Range(lo, hi) => self
-                .doc_name(within, lo)
-                .append(self.allocator.text(" upto "))
-                .append(self.doc_name(within, hi)),
+            Type::Range(lo, hi, step) => {
+                let range = self
+                    .doc_name(within, lo)
+                    .append(self.allocator.text(" upto "))
+                    .append(self.doc_name(within, hi));
+
+                match step {
+                    Some(step) => range
+                        .append(self.allocator.text(" by "))
+                        .append(self.doc_name(within, step)),
+                    None => range,
+                }
+            }
             Type::Fun(t, u) => self
                 .allocator
                 .intersperse(
@@ -138,6 +171,7 @@ impl<'a> Prettier<'a> {
                 self.allocator.text(" * "),
             ),
             Type::Number => self.allocator.text("<number>"),
+            Type::Text => self.allocator.text("text"),
             Type::Invalid => self.allocator.text("<error>"),
         }
     }
@@ -235,6 +269,11 @@ impl<'a> Prettier<'a> {
                 .append(self.allocator.text(" as "))
                 .append(self.doc_type(within, to))
                 .group(),
+            StmtNode::Neg { name, of } => self
+                .doc_let(within, name)
+                .append(self.allocator.text("-"))
+                .append(self.doc_name(within, of))
+                .group(),
         }
     }
 
@@ -281,6 +320,7 @@ impl<'a> Prettier<'a> {
     ) -> DocBuilder<Arena<'a>> {
         match &value.node {
             StaticValueNode::Num(n) => self.allocator.text(format!("{n}")),
+            StaticValueNode::Text(text) => self.allocator.text(format!("{text:?}")),
             StaticValueNode::LateInit(block) => self.doc_block(within, block),
         }
     }
@@ -288,6 +328,7 @@ impl<'a> Prettier<'a> {
     fn doc_value(&'a self, within: Option<&Name>, value: &Value) -> DocBuilder<Arena<'a>> {
         match &value.node {
             ValueNode::Num(n) => self.allocator.text(format!("{n}")),
+            ValueNode::Text(text) => self.allocator.text(format!("{text:?}")),
             ValueNode::Name(name) => self.doc_name(within, name),
             ValueNode::Invalid => self.allocator.text("<error>"),
         }
@@ -309,7 +350,7 @@ impl<'a> Prettier<'a> {
             Actual::Lit(lit) => self.allocator.text(format!(".{lit}")),
             Actual::Generated(id) => self.allocator.text(format!(".{}", String::from(*id))),
             Actual::Root => self.allocator.text("root"),
-            Actual::Scope(_) => self.allocator.nil(),
+            Actual::File(_) | Actual::Scope(_) => self.allocator.nil(),
         })
     }
 }
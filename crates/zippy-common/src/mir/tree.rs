@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use super::TypeId;
+use crate::hir::Extern;
 use crate::message::Span;
 use crate::names::Name;
 use crate::Number;
@@ -9,18 +10,40 @@ use crate::Number;
 pub struct Decls {
     pub defs: Vec<ValueDef>,
 
-    pub values: HashMap<Name, StaticValue>,
-    pub functions: HashMap<Name, (Vec<Name>, Block)>,
+    /// Values and functions are kept sorted by name, and so in the order they
+    /// were created, so that everything built from them comes out the same
+    /// every time.
+    pub values: BTreeMap<Name, StaticValue>,
+    pub functions: BTreeMap<Name, (Vec<Name>, Block)>,
+
+    /// Functions implemented in C. These have no body, and are never
+    /// evaluated.
+    pub externs: BTreeMap<Name, Extern>,
 }
 
 impl Decls {
     pub fn new(defs: Vec<ValueDef>) -> Self {
         Self {
             defs,
-            values: HashMap::new(),
-            functions: HashMap::new(),
+            values: BTreeMap::new(),
+            functions: BTreeMap::new(),
+            externs: BTreeMap::new(),
         }
     }
+
+    /// Count every statement in every definition, value and function,
+    /// including those in nested blocks.
+    pub fn statements(&self) -> usize {
+        let defs = self.defs.iter().map(|def| def.bind.statements());
+        let values = self.values.values().map(|value| match &value.node {
+            StaticValueNode::Num(_) | StaticValueNode::Text(_) => 0,
+            StaticValueNode::LateInit(block) => block.statements(),
+        });
+
+        let functions = self.functions.values().map(|(_, body)| body.statements());
+
+        defs.chain(values).chain(functions).sum()
+    }
 }
 
 #[derive(Debug)]
@@ -47,6 +70,20 @@ impl Block {
             ty,
         }
     }
+
+    /// Count the statements in this block, including those in nested blocks.
+    pub fn statements(&self) -> usize {
+        self.stmts
+            .iter()
+            .map(|stmt| match &stmt.node {
+                StmtNode::Join { body, .. } | StmtNode::Function { body, .. } => {
+                    1 + body.statements()
+                }
+
+                _ => 1,
+            })
+            .sum()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -101,6 +138,12 @@ pub enum StmtNode {
         from: TypeId,
         to: TypeId,
     },
+    /// The negation of a number. The number may be of a different numeric type,
+    /// such as a range which can't hold its negation.
+    Neg {
+        name: Name,
+        of: Name,
+    },
 }
 
 /// A static value is one that is alive for the entire duration of the program.
@@ -114,7 +157,7 @@ pub struct StaticValue {
 impl StaticValue {
     pub fn needs_late_init(&self) -> bool {
         match &self.node {
-            StaticValueNode::Num(_) => false,
+            StaticValueNode::Num(_) | StaticValueNode::Text(_) => false,
             StaticValueNode::LateInit(_) => true,
         }
     }
@@ -123,6 +166,7 @@ impl StaticValue {
 #[derive(Clone, Debug)]
 pub enum StaticValueNode {
     Num(Number),
+    Text(String),
     LateInit(Block),
 }
 
@@ -136,6 +180,7 @@ pub struct Value {
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum ValueNode {
     Num(Number),
+    Text(String),
     Name(Name),
     Invalid,
 }
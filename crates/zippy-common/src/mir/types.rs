@@ -42,6 +42,15 @@ impl Types {
         true
     }
 
+    /// The number of distinct types in the store.
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
     pub(super) fn ids(&self) -> impl Iterator<Item = TypeId> {
         (0..self.types.len()).into_iter().map(TypeId)
     }
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use bimap::BiMap;
 
 use crate::hir::BindId;
-use crate::message::Span;
+use crate::message::{File, Span};
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct GeneratedName(usize);
@@ -20,7 +20,9 @@ impl From<GeneratedName> for String {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+/// Names are numbered in the order they are created, which is also the order
+/// they are sorted in.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Name(usize);
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -35,6 +37,10 @@ impl Path {
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Actual {
     Root,
+
+    /// The top-level scope of a source file.
+    File(File),
+
     Lit(String),
     Scope(BindId),
     Generated(GeneratedName),
@@ -4,11 +4,14 @@
 //! from the root through all the names that "contain" this one.
 
 use crate::hir::BindId;
-use crate::message::Span;
+use crate::message::{File, Span};
 
 /// A literal name or a generated one.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum NamePart {
+    /// The top-level scope of a source file.
+    File(File),
+
     Source(String),
 
     /// A name identified by its scope.
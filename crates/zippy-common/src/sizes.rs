@@ -1,11 +1,268 @@
-pub fn min_range_size(lo: i64, hi: i64) -> usize {
-    let range = if 0 < lo {
-        hi as usize
-    } else if 0 > hi {
-        -(lo as i128) as usize
-    } else {
-        (hi - lo) as usize
-    };
-
-    ((range as f64).log2() / 8.0).ceil() as usize
+//! The sizes and alignments of types on the targets we compile for. Range types
+//! are represented by the smallest fixed-width integer type which can hold
+//! every value in the range, as long as the target has an integer type that
+//! wide. Those types are equally wide everywhere, but how they are aligned, and
+//! so how structs made of them are laid out, depends on the target.
+
+use target_lexicon::{Architecture, OperatingSystem, PointerWidth, Triple};
+
+use crate::Number;
+
+/// The sizes and alignments of the C types on some target, in bytes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DataLayout {
+    pub pointer_size: usize,
+
+    pub short_size: usize,
+    pub int_size: usize,
+    pub long_size: usize,
+    pub long_long_size: usize,
+
+    /// The largest alignment any scalar type needs. Types are aligned to their
+    /// size, up to this.
+    pub max_align: usize,
+}
+
+impl DataLayout {
+    pub fn host() -> Self {
+        Self::from_triple(&Triple::host())
+    }
+
+    pub fn from_triple(triple: &Triple) -> Self {
+        let windows = triple.operating_system == OperatingSystem::Windows;
+
+        match triple.pointer_width() {
+            Ok(PointerWidth::U16) => Self {
+                pointer_size: 2,
+                short_size: 2,
+                int_size: 2,
+                long_size: 4,
+                long_long_size: 8,
+                max_align: match triple.architecture {
+                    Architecture::Avr => 1,
+                    _ => 2,
+                },
+            },
+
+            Ok(PointerWidth::U32) => Self {
+                pointer_size: 4,
+                short_size: 2,
+                int_size: 4,
+                long_size: 4,
+                long_long_size: 8,
+                max_align: match triple.architecture {
+                    Architecture::X86_32(_) if !windows => 4,
+                    _ => 8,
+                },
+            },
+
+            // 64-bit Windows keeps `long` at 32 bits, while everything else
+            // makes it as wide as a pointer.
+            Ok(PointerWidth::U64) | Err(()) => Self {
+                pointer_size: 8,
+                short_size: 2,
+                int_size: 4,
+                long_size: if windows { 4 } else { 8 },
+                long_long_size: 8,
+                max_align: 8,
+            },
+        }
+    }
+
+    /// Get the alignment of a scalar type of the given size.
+    pub fn align_of(&self, size: usize) -> usize {
+        size.min(self.max_align)
+    }
+
+    /// Get the layout of a scalar type of the given size.
+    pub fn scalar(&self, size: usize) -> Layout {
+        Layout {
+            size,
+            align: self.align_of(size),
+        }
+    }
+
+    /// Get the layout of a pointer.
+    pub fn pointer(&self) -> Layout {
+        self.scalar(self.pointer_size)
+    }
+
+    /// Get the order to declare the fields of a struct in, so that it needs as
+    /// little padding as possible. Fields with a larger alignment go first,
+    /// while fields which are aligned the same way keep their order.
+    pub fn field_order(&self, fields: &[Layout]) -> Vec<usize> {
+        let mut order: Vec<_> = (0..fields.len()).collect();
+        order.sort_by_key(|index| std::cmp::Reverse(fields[*index].align));
+        order
+    }
+
+    /// Get the layout of a struct with the given fields, declared in the given
+    /// order, along with the offset of each field.
+    pub fn struct_layout(&self, fields: &[Layout]) -> (Layout, Vec<usize>) {
+        let mut offsets = Vec::with_capacity(fields.len());
+        let mut size = 0;
+        let mut align = 1;
+
+        for field in fields {
+            size = size.next_multiple_of(field.align);
+            offsets.push(size);
+            size += field.size;
+            align = align.max(field.align);
+        }
+
+        let size = size.next_multiple_of(align);
+        (Layout { size, align }, offsets)
+    }
+
+    /// Get the smallest integer type able to hold every value from `lo` to
+    /// `hi`, or `None` if the range is too large for any integer type on this
+    /// target.
+    pub fn integer_type(&self, lo: &Number, hi: &Number) -> Option<IntegerType> {
+        [1, 2, 4, 8]
+            .into_iter()
+            .take_while(|size| *size <= self.long_long_size)
+            .flat_map(|size| [false, true].map(|signed| IntegerType { signed, size }))
+            .find(|ty| &ty.min() <= lo && hi <= &ty.max())
+    }
+}
+
+impl Default for DataLayout {
+    fn default() -> Self {
+        Self::host()
+    }
+}
+
+/// The size and alignment of a C type, in bytes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Layout {
+    pub size: usize,
+    pub align: usize,
+}
+
+/// A fixed-width integer type.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct IntegerType {
+    pub signed: bool,
+
+    /// The size of the type in bytes.
+    pub size: usize,
+}
+
+impl IntegerType {
+    /// Get the name of this type in `<stdint.h>`.
+    pub fn c_name(&self) -> String {
+        let bits = self.size * 8;
+        if self.signed {
+            format!("int{bits}_t")
+        } else {
+            format!("uint{bits}_t")
+        }
+    }
+
+    pub fn min(&self) -> Number {
+        if self.signed {
+            -Number::from(1u64 << (self.size * 8 - 1))
+        } else {
+            Number::from(0u64)
+        }
+    }
+
+    pub fn max(&self) -> Number {
+        if self.signed {
+            Number::from((1u64 << (self.size * 8 - 1)) - 1)
+        } else {
+            Number::from(u64::MAX >> (64 - self.size * 8))
+        }
+    }
+}
+
+/// Get the size in bytes of the smallest integer type able to hold every value
+/// from `lo` to `hi` on the given target.
+pub fn min_range_size(layout: &DataLayout, lo: i64, hi: i64) -> Option<usize> {
+    layout
+        .integer_type(&Number::from(lo), &Number::from(hi))
+        .map(|ty| ty.size)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use target_lexicon::Triple;
+
+    use super::{DataLayout, Layout};
+
+    fn layout(triple: &str) -> DataLayout {
+        DataLayout::from_triple(&Triple::from_str(triple).unwrap())
+    }
+
+    #[test]
+    fn c_types_per_target() {
+        let avr = layout("avr-unknown-unknown");
+        let i686 = layout("i686-unknown-linux-gnu");
+        let windows = layout("x86_64-pc-windows-msvc");
+        let linux = layout("x86_64-unknown-linux-gnu");
+
+        assert_eq!((avr.pointer_size, avr.int_size, avr.long_size), (2, 2, 4));
+        assert_eq!(
+            (i686.pointer_size, i686.int_size, i686.long_size),
+            (4, 4, 4)
+        );
+        assert_eq!(
+            (windows.pointer_size, windows.int_size, windows.long_size),
+            (8, 4, 4)
+        );
+        assert_eq!(
+            (linux.pointer_size, linux.int_size, linux.long_size),
+            (8, 4, 8)
+        );
+
+        assert_eq!(
+            [avr, i686, windows].map(|layout| layout.align_of(8)),
+            [1, 4, 8]
+        );
+    }
+
+    #[test]
+    fn struct_layout_per_target() {
+        // A `uint8_t`, a `uint64_t` and a `uint16_t`, in that order.
+        let expected = [
+            (
+                "avr-unknown-unknown",
+                (11, 1),
+                vec![0, 1, 9],
+                vec![0, 1, 2],
+                11,
+            ),
+            (
+                "i686-unknown-linux-gnu",
+                (16, 4),
+                vec![0, 4, 12],
+                vec![1, 2, 0],
+                12,
+            ),
+            (
+                "x86_64-pc-windows-msvc",
+                (24, 8),
+                vec![0, 8, 16],
+                vec![1, 2, 0],
+                16,
+            ),
+        ];
+
+        for (triple, (size, align), offsets, order, packed) in expected {
+            let layout = layout(triple);
+            let fields = [1, 8, 2].map(|size| layout.scalar(size));
+
+            let (actual, actual_offsets) = layout.struct_layout(&fields);
+            assert_eq!(actual, Layout { size, align }, "{triple}");
+            assert_eq!(actual_offsets, offsets, "{triple}");
+
+            let actual_order = layout.field_order(&fields);
+            assert_eq!(actual_order, order, "{triple}");
+
+            let reordered: Vec<_> = actual_order.iter().map(|index| fields[*index]).collect();
+            assert_eq!(layout.struct_layout(&reordered).0.size, packed, "{triple}");
+        }
+    }
 }
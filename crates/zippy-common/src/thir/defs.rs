@@ -1,19 +1,19 @@
 //! Keeps track of type definitions.
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use super::Type;
 use crate::names::Name;
 
 #[derive(Debug, Default)]
 pub struct Definitions {
-    types: HashMap<Name, Type>,
+    types: BTreeMap<Name, Type>,
 }
 
 impl Definitions {
     pub fn new() -> Self {
         Self {
-            types: HashMap::new(),
+            types: BTreeMap::new(),
         }
     }
 
@@ -44,7 +44,7 @@ impl Definitions {
 }
 
 pub struct DefsIter {
-    types: std::collections::hash_map::IntoIter<Name, Type>,
+    types: std::collections::btree_map::IntoIter<Name, Type>,
 }
 
 impl Iterator for DefsIter {
@@ -149,10 +149,17 @@ impl<'a> Prettier<'a> {
 
     fn pretty_range(&mut self, ty: &Type) -> String {
         match ty {
-            Type::Range(lo, hi) => {
+            Type::Range(lo, hi, None) => {
                 format!("{} upto {}", self.pretty_name(lo), self.pretty_name(hi))
             }
 
+            Type::Range(lo, hi, Some(step)) => format!(
+                "{} upto {} by {}",
+                self.pretty_name(lo),
+                self.pretty_name(hi),
+                self.pretty_name(step)
+            ),
+
             Type::Var(_, var) => {
                 if let Some(ty) = self.subst.get(var) {
                     self.pretty_range(ty)
@@ -199,6 +206,7 @@ impl<'a> Prettier<'a> {
             }
 
             Type::Number => "<number>".into(),
+            Type::Text => "text".into(),
             Type::Type => "type".into(),
 
             Type::Invalid => "<error>".into(),
@@ -1,6 +1,7 @@
 pub use super::Type;
 
 use super::CoercionId;
+use crate::hir::Extern;
 use crate::message::Span;
 use crate::names::Name;
 use crate::Number;
@@ -39,6 +40,7 @@ pub struct Expr<Data = ()> {
 pub enum ExprNode<Data> {
     Name(Name),
     Num(Number),
+    Str(String),
 
     Lam(Pat<Data>, Box<Expr<Data>>),
     App(Box<Expr<Data>>, Box<Expr<Data>>),
@@ -48,7 +50,9 @@ pub enum ExprNode<Data> {
     Coerce(Box<Expr<Data>>, CoercionId),
 
     Tuple(Box<Expr<Data>>, Box<Expr<Data>>),
+    Neg(Box<Expr<Data>>),
 
+    Extern(Extern),
     Hole,
     Invalid,
 }
@@ -0,0 +1,255 @@
+//! The bridge converts the results of the salsa-based typechecker into the
+//! older representation still used by the midend. The typed HIR is translated
+//! to the THIR more or less one-to-one, while every interned [`names2::Name`]
+//! is given a counterpart in a fresh [`Names`] table.
+//!
+//! This module should go away once the midend works directly with the new
+//! representations.
+
+mod tree;
+mod types;
+
+use std::collections::HashMap;
+
+use zippy_common::hir2::{self, TypeOrSchema};
+use zippy_common::message::Span;
+use zippy_common::names::{self, Actual, Names, Path};
+use zippy_common::names2::{self, NamePart};
+use zippy_common::thir;
+
+use crate::{resolved, Db};
+
+pub struct Bridged {
+    pub checked: thir::TypeckResult,
+    pub names: Names,
+
+    /// A mapping from every converted name to its counterpart.
+    pub name_map: HashMap<names2::Name, names::Name>,
+}
+
+pub fn bridge(
+    db: &dyn Db,
+    decls: resolved::Decls,
+    defs: hir2::Definitions,
+    res: hir2::TypeckResult,
+) -> Bridged {
+    let mut bridge = Bridge::new(db, res.coercions(bridge_db(db)));
+    bridge.declare_types(decls);
+
+    let common = bridge_db(db);
+    let values = res.decls(common).values(common);
+    for def in values.iter() {
+        bridge.declare_pat(&def.pat);
+    }
+
+    // New names are created in the order they are first seen, so everything
+    // is visited in a fixed order to always end up with the same names.
+    let mut types: Vec<_> = defs.types(common).iter().collect();
+    types.sort_by_key(|(name, _)| **name);
+
+    let mut tydefs = thir::Definitions::new();
+    for (name, ty) in types {
+        let name = bridge.name(*name);
+        let ty = bridge.lower_type(ty);
+        tydefs.add(name, ty);
+    }
+
+    let values = values
+        .into_iter()
+        .map(|def| bridge.value_def(def))
+        .collect();
+
+    let mut vars: Vec<_> = res.subst(common).into_iter().collect();
+    vars.sort_by_key(|(var, _)| *var);
+
+    let mut subst = HashMap::new();
+    for (var, (inst, ty)) in vars {
+        let var = bridge.var(var);
+        let inst = bridge.lower_inst(&inst);
+        let ty = bridge.lower_type(&ty);
+        subst.insert(var, (inst, ty));
+    }
+
+    let context = res.context(common);
+    let mut context: Vec<_> = context.iter().collect();
+    context.sort_by_key(|(name, _)| **name);
+
+    for (name, ty) in context {
+        let name = bridge.name(*name);
+        match ty {
+            TypeOrSchema::Type(ty) => {
+                let ty = bridge.lower_type(ty);
+                bridge.context.add(name, ty);
+            }
+
+            TypeOrSchema::Schema(params, ty) => {
+                let params = params.iter().map(|param| bridge.name(*param)).collect();
+                let ty = bridge.lower_type(ty);
+                bridge.context.add_schema(name, params, ty);
+            }
+        }
+    }
+
+    let checked = thir::TypeckResult {
+        coercions: bridge.coercions,
+        context: bridge.context,
+        defs: tydefs,
+
+        // The midend gets everything it needs to know about type definitions
+        // from `defs`.
+        decls: thir::Decls {
+            values,
+            types: Vec::new(),
+        },
+
+        subst,
+
+        // Any unsolved constraints have already been reported by the
+        // typechecker.
+        constraints: Vec::new(),
+    };
+
+    Bridged {
+        checked,
+        names: bridge.names,
+        name_map: bridge.name_map,
+    }
+}
+
+struct Bridge<'a> {
+    db: &'a dyn Db,
+
+    names: Names,
+    root: names::Name,
+    name_map: HashMap<names2::Name, names::Name>,
+
+    /// The spans of every declared name, where known.
+    spans: HashMap<names2::Name, Span>,
+
+    /// The span used for names which don't have a declaration span.
+    at: Span,
+
+    vars: HashMap<hir2::UniVar, thir::UniVar>,
+    context: thir::Context,
+
+    coercion_ids: HashMap<hir2::CoercionId, thir::CoercionId>,
+    old_coercions: hir2::Coercions,
+    coercions: thir::Coercions,
+}
+
+impl<'a> Bridge<'a> {
+    fn new(db: &'a dyn Db, old_coercions: hir2::Coercions) -> Self {
+        let mut names = Names::new();
+        let root = names.root();
+
+        Self {
+            db,
+
+            names,
+            root,
+            name_map: HashMap::new(),
+
+            spans: HashMap::new(),
+            at: Span::new(0, 0, 0),
+
+            vars: HashMap::new(),
+            context: thir::Context::new(),
+
+            coercion_ids: HashMap::new(),
+            old_coercions,
+            coercions: thir::Coercions::new(),
+        }
+    }
+
+    /// Record the declaration span of every name bound by a type definition.
+    fn declare_types(&mut self, decls: resolved::Decls) {
+        fn declare(spans: &mut HashMap<names2::Name, Span>, pat: &resolved::Pat) {
+            match &pat.node {
+                resolved::PatNode::Name(name) => {
+                    spans.insert(*name, pat.span);
+                }
+
+                resolved::PatNode::Tuple(a, b) => {
+                    declare(spans, a);
+                    declare(spans, b);
+                }
+
+                resolved::PatNode::Anno(pat, _) => declare(spans, pat),
+                resolved::PatNode::Wildcard | resolved::PatNode::Invalid => {}
+            }
+        }
+
+        for def in decls.types(self.db).iter() {
+            declare(&mut self.spans, &def.pat);
+        }
+    }
+
+    /// Record the declaration span of every name bound by this pattern.
+    fn declare_pat(&mut self, pat: &hir2::Pat) {
+        match &pat.node {
+            hir2::PatNode::Name(name) => {
+                self.spans.insert(*name, pat.span);
+            }
+
+            hir2::PatNode::Tuple(a, b) => {
+                self.declare_pat(a);
+                self.declare_pat(b);
+            }
+
+            hir2::PatNode::Anno(pat, _) | hir2::PatNode::Coerce(pat, _) => self.declare_pat(pat),
+            hir2::PatNode::Wildcard | hir2::PatNode::Invalid => {}
+        }
+    }
+
+    /// Get the old name corresponding to the given interned name, creating it
+    /// (and its parents) if necessary.
+    fn name(&mut self, name: names2::Name) -> names::Name {
+        if let Some(converted) = self.name_map.get(&name) {
+            return *converted;
+        }
+
+        let common = bridge_db(self.db);
+        let ctx = match name.path(common) {
+            Some(parent) => self.name(parent),
+            None => self.root,
+        };
+
+        let span = self.spans.get(&name).copied().unwrap_or(self.at);
+
+        let converted = match name.name(common) {
+            NamePart::Source(text) => {
+                let actual = Actual::Lit(text.clone());
+                self.names.add(span, Path::new(ctx, actual))
+            }
+
+            NamePart::File(id) => self.names.add(span, Path::new(ctx, Actual::File(*id))),
+            NamePart::Scope(id) => self.names.add(span, Path::new(ctx, Actual::Scope(*id))),
+            NamePart::Spanned(span) => self.names.fresh(*span, ctx),
+        };
+
+        self.name_map.insert(name, converted);
+        converted
+    }
+
+    fn var(&mut self, var: hir2::UniVar) -> thir::UniVar {
+        *self.vars.entry(var).or_insert_with(|| self.context.fresh())
+    }
+
+    fn coercion(&mut self, id: hir2::CoercionId) -> thir::CoercionId {
+        if let Some(converted) = self.coercion_ids.get(&id) {
+            return *converted;
+        }
+
+        let converted = self.coercions.fresh();
+        if let Some(hir2::Coercion::Upcast) = self.old_coercions.get(&id) {
+            self.coercions.add(converted, thir::Coercion::Upcast);
+        }
+
+        self.coercion_ids.insert(id, converted);
+        converted
+    }
+}
+
+fn bridge_db(db: &dyn Db) -> &dyn zippy_common::Db {
+    <dyn Db as salsa::DbWithJar<zippy_common::Jar>>::as_jar_db(db)
+}
@@ -0,0 +1,133 @@
+use zippy_common::hir2;
+use zippy_common::thir;
+
+use super::Bridge;
+
+type LoValueDef = thir::ValueDef<thir::Type>;
+type LoExpr = thir::Expr<thir::Type>;
+type LoPat = thir::Pat<thir::Type>;
+
+impl Bridge<'_> {
+    pub fn value_def(&mut self, def: hir2::ValueDef) -> LoValueDef {
+        self.at = def.span;
+
+        let implicits = def
+            .implicits
+            .iter()
+            .map(|name| (self.name(*name), def.span))
+            .collect();
+
+        let anno = self.lower_type(&def.pat.data);
+        let pat = self.pat(def.pat);
+        let bind = self.expr(def.body);
+
+        thir::ValueDef {
+            span: def.span,
+            implicits,
+            pat,
+            anno,
+            bind,
+        }
+    }
+
+    fn expr(&mut self, expr: hir2::Expr) -> LoExpr {
+        let data = self.lower_type(&expr.data);
+
+        let node = match expr.node {
+            hir2::ExprNode::Name(name) => thir::ExprNode::Name(self.name(name)),
+            hir2::ExprNode::Num(num) => thir::ExprNode::Num(num),
+            hir2::ExprNode::Str(text) => thir::ExprNode::Str(text),
+
+            hir2::ExprNode::Lam(param, body) => {
+                self.declare_pat(&param);
+                let param = self.pat(param);
+                let body = Box::new(self.expr(*body));
+                thir::ExprNode::Lam(param, body)
+            }
+
+            hir2::ExprNode::App(fun, arg) => {
+                let fun = Box::new(self.expr(*fun));
+                let arg = Box::new(self.expr(*arg));
+                thir::ExprNode::App(fun, arg)
+            }
+
+            hir2::ExprNode::Inst(name, args) => {
+                let of = Box::new(thir::Expr {
+                    node: thir::ExprNode::Name(self.name(name)),
+                    span: expr.span,
+                    data: data.clone(),
+                });
+
+                let args = args
+                    .into_iter()
+                    .map(|(span, ty)| (span, self.lower_type(&ty)))
+                    .collect();
+
+                thir::ExprNode::Inst(of, args)
+            }
+
+            hir2::ExprNode::Anno(expr, span, ty) => {
+                let expr = Box::new(self.expr(*expr));
+                let ty = self.lower_type(&ty);
+                thir::ExprNode::Anno(expr, span, ty)
+            }
+
+            hir2::ExprNode::Coerce(expr, id) => {
+                let expr = Box::new(self.expr(*expr));
+                thir::ExprNode::Coerce(expr, self.coercion(id))
+            }
+
+            hir2::ExprNode::Tuple(x, y) => {
+                let x = Box::new(self.expr(*x));
+                let y = Box::new(self.expr(*y));
+                thir::ExprNode::Tuple(x, y)
+            }
+
+            hir2::ExprNode::Neg(x) => thir::ExprNode::Neg(Box::new(self.expr(*x))),
+
+            hir2::ExprNode::Extern(external) => thir::ExprNode::Extern(external),
+            hir2::ExprNode::Hole => thir::ExprNode::Hole,
+            hir2::ExprNode::Invalid => thir::ExprNode::Invalid,
+        };
+
+        thir::Expr {
+            node,
+            span: expr.span,
+            data,
+        }
+    }
+
+    fn pat(&mut self, pat: hir2::Pat) -> LoPat {
+        let data = self.lower_type(&pat.data);
+
+        let node = match pat.node {
+            hir2::PatNode::Name(name) => thir::PatNode::Name(self.name(name)),
+
+            hir2::PatNode::Tuple(a, b) => {
+                let a = Box::new(self.pat(*a));
+                let b = Box::new(self.pat(*b));
+                thir::PatNode::Tuple(a, b)
+            }
+
+            hir2::PatNode::Anno(pat, ty) => {
+                let pat = Box::new(self.pat(*pat));
+                let ty = self.lower_type(&ty);
+                thir::PatNode::Anno(pat, ty)
+            }
+
+            hir2::PatNode::Coerce(pat, id) => {
+                let pat = Box::new(self.pat(*pat));
+                thir::PatNode::Coerce(pat, self.coercion(id))
+            }
+
+            hir2::PatNode::Wildcard => thir::PatNode::Wildcard,
+            hir2::PatNode::Invalid => thir::PatNode::Invalid,
+        };
+
+        thir::Pat {
+            node,
+            span: pat.span,
+            data,
+        }
+    }
+}
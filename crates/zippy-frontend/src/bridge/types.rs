@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use zippy_common::hir2;
+use zippy_common::names;
+use zippy_common::names2::Name;
+use zippy_common::thir;
+
+use super::Bridge;
+
+impl Bridge<'_> {
+    pub fn lower_type(&mut self, ty: &hir2::Type) -> thir::Type {
+        match ty {
+            hir2::Type::Name(name) => thir::Type::Name(self.name(*name)),
+            hir2::Type::Range(lo, hi, step) => thir::Type::Range(
+                self.name(*lo),
+                self.name(*hi),
+                step.map(|step| self.name(step)),
+            ),
+
+            hir2::Type::Fun(t, u) => {
+                let t = Box::new(self.lower_type(t));
+                let u = Box::new(self.lower_type(u));
+                thir::Type::Fun(t, u)
+            }
+
+            hir2::Type::Product(t, u) => {
+                let t = Box::new(self.lower_type(t));
+                let u = Box::new(self.lower_type(u));
+                thir::Type::Product(t, u)
+            }
+
+            hir2::Type::Instantiated(ty, inst) => {
+                let ty = Box::new(self.lower_type(ty));
+                let inst = self.lower_inst(inst);
+                thir::Type::Instantiated(ty, inst)
+            }
+
+            hir2::Type::Var(mutability, var) => {
+                let mutability = match mutability {
+                    hir2::Mutability::Mutable => thir::Mutability::Mutable,
+                    hir2::Mutability::Immutable => thir::Mutability::Immutable,
+                };
+
+                thir::Type::Var(mutability, self.var(*var))
+            }
+
+            hir2::Type::Number => thir::Type::Number,
+            hir2::Type::Text => thir::Type::Text,
+            hir2::Type::Type => thir::Type::Type,
+            hir2::Type::Invalid => thir::Type::Invalid,
+        }
+    }
+
+    pub fn lower_inst(
+        &mut self,
+        inst: &HashMap<Name, hir2::Type>,
+    ) -> HashMap<names::Name, thir::Type> {
+        let mut inst: Vec<_> = inst.iter().collect();
+        inst.sort_by_key(|(name, _)| **name);
+
+        inst.into_iter()
+            .map(|(name, ty)| (self.name(*name), self.lower_type(ty)))
+            .collect()
+    }
+}
@@ -2,7 +2,7 @@
 //! `m` if `m` occurs in the definition of `n`. This considers dependencies
 //! between both value- and type names.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use zippy_common::names2::Name;
 
@@ -11,7 +11,7 @@ use crate::Db;
 
 /// A [`DefIndex`] is an index into either the `values` or the `types` list in a
 /// [`Decls`].
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum DefIndex {
     Value(usize),
     Type(usize),
@@ -24,7 +24,7 @@ pub struct Dependencies {
 }
 
 impl Dependencies {
-    pub fn find(db: &dyn Db, decls: Decls) -> HashMap<DefIndex, HashSet<DefIndex>> {
+    pub fn find(db: &dyn Db, decls: Decls) -> BTreeMap<DefIndex, BTreeSet<DefIndex>> {
         let mut finder = Self::default();
 
         for (index, def) in decls.types(db).iter().enumerate() {
@@ -35,20 +35,18 @@ impl Dependencies {
             finder.search_value(def, index);
         }
 
-        finder
-            .deps
-            .into_iter()
-            .map(|(key, values)| {
-                let key = finder.map.get(&key).unwrap();
-                let values = values
-                    .into_iter()
-                    .map(|name| finder.map.get(&name).unwrap())
-                    .copied()
-                    .collect();
-
-                (*key, values)
-            })
-            .collect()
+        // A definition may define several names, in which case it depends on
+        // everything any of them depend on.
+        let mut graph: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
+        for (name, deps) in finder.deps {
+            let deps = deps.into_iter().map(|name| *finder.map.get(&name).unwrap());
+            graph
+                .entry(*finder.map.get(&name).unwrap())
+                .or_default()
+                .extend(deps);
+        }
+
+        graph
     }
 
     fn search_type(&mut self, def: &TypeDef, index: usize) {
@@ -118,10 +116,12 @@ fn pat_defines(pat: &Pat) -> (HashSet<Name>, HashSet<Name>) {
 
 fn type_refers(shadowed: &HashSet<Name>, ty: &Type) -> HashSet<Name> {
     match &ty.node {
-        TypeNode::Invalid | TypeNode::Number | TypeNode::Wildcard | TypeNode::Type => {
-            HashSet::new()
-        }
-        TypeNode::Range(lo, hi) => HashSet::from([*lo, *hi]),
+        TypeNode::Invalid
+        | TypeNode::Number
+        | TypeNode::Text
+        | TypeNode::Wildcard
+        | TypeNode::Type => HashSet::new(),
+        TypeNode::Range(lo, hi, step) => [*lo, *hi].into_iter().chain(*step).collect(),
 
         TypeNode::Name(name) if shadowed.contains(name) => HashSet::new(),
         TypeNode::Name(name) => HashSet::from([*name]),
@@ -136,10 +136,15 @@ fn type_refers(shadowed: &HashSet<Name>, ty: &Type) -> HashSet<Name> {
 
 fn expr_refers(shadowed: &HashSet<Name>, ex: &Expr) -> HashSet<Name> {
     match &ex.node {
-        ExprNode::Invalid | ExprNode::Num(_) | ExprNode::Hole => HashSet::new(),
+        ExprNode::Invalid
+        | ExprNode::Num(_)
+        | ExprNode::Str(_)
+        | ExprNode::Extern(_)
+        | ExprNode::Hole => HashSet::new(),
 
         ExprNode::Name(name) if shadowed.contains(name) => HashSet::new(),
         ExprNode::Name(name) => HashSet::from([*name]),
+        ExprNode::Neg(ex) => expr_refers(shadowed, ex),
 
         ExprNode::Anno(ex, ty) => {
             let ex = expr_refers(shadowed, ex);
@@ -5,22 +5,25 @@
 //!
 //! This implementation is based on Tarjan's algorithm, which also produces
 //! a reverse topological sort. This means that the typer can go through the
-//! components in the order that they are produced.
+//! components in the order that they are produced. Definitions are visited in
+//! the order they are defined, so the components always come out in the same
+//! order.
 
 mod dependency;
 
 pub use dependency::DefIndex;
+pub(crate) use dependency::Dependencies;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use self::dependency::Dependencies;
 use crate::resolved::Decls;
 use crate::Db;
 
 #[salsa::tracked]
 pub struct Components {
+    /// Every component, with its definitions sorted by their index.
     #[return_ref]
-    pub ordered: Vec<HashSet<DefIndex>>,
+    pub ordered: Vec<Vec<DefIndex>>,
 }
 
 /// Find all the strongly connected components for the given `Decls`.
@@ -55,11 +58,11 @@ struct Finder {
     stack: Vec<DefIndex>,
     on_stack: HashSet<DefIndex>,
 
-    components: Vec<HashSet<DefIndex>>,
+    components: Vec<Vec<DefIndex>>,
 }
 
 impl Finder {
-    fn connect(&mut self, graph: &HashMap<DefIndex, HashSet<DefIndex>>, vertex: DefIndex) {
+    fn connect(&mut self, graph: &BTreeMap<DefIndex, BTreeSet<DefIndex>>, vertex: DefIndex) {
         self.indicies.insert(vertex, self.index);
         self.lowlinks.insert(vertex, self.index);
         self.index += 1;
@@ -87,9 +90,9 @@ impl Finder {
         }
 
         if self.lowlinks.get(&vertex) == self.indicies.get(&vertex) {
-            let mut component = HashSet::new();
+            let mut component = Vec::new();
             while let Some(child) = self.stack.pop() {
-                component.insert(child);
+                component.push(child);
                 self.on_stack.remove(&child);
 
                 if child == vertex {
@@ -97,6 +100,7 @@ impl Finder {
                 }
             }
 
+            component.sort();
             self.components.push(component);
         }
     }
@@ -0,0 +1,217 @@
+//! Textual representations of the intermediate forms of the frontend, which
+//! are only meant for debugging the compiler.
+
+use std::fmt::Write;
+
+use zippy_common::hir2::{self, pretty_type, PrettyMap, TypeckResult};
+use zippy_common::kinds::Kind;
+use zippy_common::names2::{Name, NamePart};
+
+use crate::components::{components, DefIndex, Dependencies};
+use crate::kick::Kinds;
+use crate::lex::Tokens;
+use crate::{parse, resolved, unresolved, Db};
+
+/// Write every token on its own line, after its span.
+pub fn tokens(db: &dyn Db, tokens: Tokens) -> String {
+    let mut res = String::new();
+    for (token, span) in tokens.tokens(db) {
+        writeln!(res, "{}..{}\t{token:?}", span.start, span.end).unwrap();
+    }
+
+    res
+}
+
+/// Parse the tokens again, keeping the concrete syntax tree this time.
+pub fn cst(db: &dyn Db, tokens: Tokens) -> String {
+    let (decls, _) = parse::parse_concrete(tokens.tokens(db).clone(), tokens.file(db));
+    format!("{decls:#?}\n")
+}
+
+pub fn unresolved(db: &dyn Db, decls: unresolved::Decls) -> String {
+    format!(
+        "values: {:#?}\ntypes: {:#?}\n",
+        decls.values(db),
+        decls.types(db)
+    )
+}
+
+pub fn resolved(db: &dyn Db, decls: resolved::Decls) -> String {
+    format!(
+        "values: {:#?}\ntypes: {:#?}\n",
+        decls.values(db),
+        decls.types(db)
+    )
+}
+
+/// Render the dependency graph between definitions as a Graphviz graph. Type
+/// definitions are boxes and value definitions are ellipses, and every group
+/// of mutually recursive definitions is put in its own cluster.
+pub fn dependencies(db: &dyn Db, decls: resolved::Decls) -> String {
+    let common = common_db(db);
+    let graph = Dependencies::find(db, decls);
+
+    let label = |index: &DefIndex| {
+        let pat = match index {
+            DefIndex::Value(index) => &decls.values(db)[*index].pat,
+            DefIndex::Type(index) => &decls.types(db)[*index].pat,
+        };
+
+        let mut names = Vec::new();
+        resolved_names(pat, &mut names);
+
+        let mut names: Vec<_> = names.iter().map(|name| name_text(common, name)).collect();
+        names.sort();
+        escape(&names.join(", "))
+    };
+
+    let mut res = String::from("digraph dependencies {\n");
+
+    for (index, component) in components(db, decls).ordered(db).iter().enumerate() {
+        let mut nodes: Vec<_> = component.iter().collect();
+        nodes.sort_by_key(|node| def_id(node));
+
+        let cyclic =
+            nodes.len() > 1 || matches!(graph.get(nodes[0]), Some(deps) if deps.contains(nodes[0]));
+
+        let indent = if cyclic {
+            writeln!(res, "\tsubgraph cluster_{index} {{").unwrap();
+            writeln!(res, "\t\tlabel=\"recursive\";").unwrap();
+            "\t\t"
+        } else {
+            "\t"
+        };
+
+        for node in nodes {
+            let shape = match node {
+                DefIndex::Value(_) => "ellipse",
+                DefIndex::Type(_) => "box",
+            };
+
+            writeln!(
+                res,
+                "{indent}{} [label=\"{}\", shape={shape}];",
+                def_id(node),
+                label(node)
+            )
+            .unwrap();
+        }
+
+        if cyclic {
+            res.push_str("\t}\n");
+        }
+    }
+
+    let mut edges: Vec<_> = graph
+        .iter()
+        .flat_map(|(from, deps)| deps.iter().map(move |to| (def_id(from), def_id(to))))
+        .collect();
+
+    edges.sort();
+
+    for (from, to) in edges {
+        writeln!(res, "\t{from} -> {to};").unwrap();
+    }
+
+    res.push_str("}\n");
+    res
+}
+
+/// Write the kind of every type definition, sorted by name.
+pub fn kinds(db: &dyn Db, kinds: Kinds) -> String {
+    let common = common_db(db);
+    let mut lines: Vec<_> = kinds
+        .kinds(db)
+        .iter()
+        .map(|(name, kind)| format!("{} :: {}\n", name_text(common, name), pretty_kind(kind)))
+        .collect();
+
+    lines.sort();
+    lines.concat()
+}
+
+/// Write the type of every name bound by a top-level value definition.
+pub fn typed(db: &dyn Db, tyckres: TypeckResult) -> String {
+    let common = common_db(db);
+    let subst = tyckres.subst(common);
+    let subst = subst.iter().map(|(var, (_, ty))| (*var, ty)).collect();
+    let mut map = PrettyMap::new();
+
+    let mut res = String::new();
+    for def in tyckres.decls(common).values(common).iter() {
+        let mut names = Vec::new();
+        pat_names(&def.pat, &mut names);
+
+        for (name, ty) in names {
+            let ty = pretty_type(common, &subst, &mut map, ty);
+            writeln!(res, "{} : {ty}", name_text(common, &name)).unwrap();
+        }
+    }
+
+    res
+}
+
+pub(crate) fn pat_names<'a>(pat: &'a hir2::Pat, into: &mut Vec<(Name, &'a hir2::Type)>) {
+    match &pat.node {
+        hir2::PatNode::Name(name) => into.push((*name, &pat.data)),
+        hir2::PatNode::Tuple(a, b) => {
+            pat_names(a, into);
+            pat_names(b, into);
+        }
+
+        hir2::PatNode::Anno(pat, _) | hir2::PatNode::Coerce(pat, _) => pat_names(pat, into),
+        hir2::PatNode::Wildcard | hir2::PatNode::Invalid => {}
+    }
+}
+
+fn resolved_names(pat: &resolved::Pat, into: &mut Vec<Name>) {
+    match &pat.node {
+        resolved::PatNode::Name(name) => into.push(*name),
+        resolved::PatNode::Tuple(a, b) => {
+            resolved_names(a, into);
+            resolved_names(b, into);
+        }
+
+        resolved::PatNode::Anno(pat, _) => resolved_names(pat, into),
+        resolved::PatNode::Wildcard | resolved::PatNode::Invalid => {}
+    }
+}
+
+/// The name of the node of a definition in a Graphviz graph.
+fn def_id(index: &DefIndex) -> String {
+    match index {
+        DefIndex::Value(index) => format!("v{index}"),
+        DefIndex::Type(index) => format!("t{index}"),
+    }
+}
+
+/// Escape a string for use in a quoted Graphviz label.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn name_text(db: &dyn zippy_common::Db, name: &Name) -> String {
+    match name.name(db) {
+        NamePart::Source(name) => name.clone(),
+        NamePart::File(file) => format!("<file {file}>"),
+        NamePart::Scope(_) => "<scope>".into(),
+        NamePart::Spanned(span) => format!("<expr at {}..{}>", span.start, span.end),
+    }
+}
+
+pub(crate) fn pretty_kind(kind: &Kind) -> String {
+    match kind {
+        Kind::Type => "type".into(),
+        Kind::Invalid => "<error>".into(),
+        Kind::Function(a, b) => match a.as_ref() {
+            Kind::Function(..) => format!("({}) -> {}", pretty_kind(a), pretty_kind(b)),
+            _ => format!("{} -> {}", pretty_kind(a), pretty_kind(b)),
+        },
+
+        Kind::Product(a, b) => format!("{} * {}", pretty_kind(a), pretty_kind(b)),
+    }
+}
+
+fn common_db(db: &dyn Db) -> &dyn zippy_common::Db {
+    <dyn Db as salsa::DbWithJar<zippy_common::Jar>>::as_jar_db(db)
+}
@@ -0,0 +1,223 @@
+use super::Formatter;
+use crate::parse::tree::{BinOp, Decl, DeclNode, Expr, ExprNode};
+
+impl Formatter<'_> {
+    pub fn decl(&mut self, decl: &Decl) {
+        match &decl.node {
+            DeclNode::ValueDecl { pat, bind } => {
+                self.out.push_str("let ");
+                self.expr(pat, false);
+                self.bind(bind.as_ref());
+            }
+
+            DeclNode::TypeDecl { pat, bind } => {
+                self.out.push_str("type ");
+                self.expr(pat, false);
+                self.bind(bind.as_ref());
+            }
+
+            DeclNode::FunDecl {
+                exported,
+                name,
+                implicits,
+                args,
+                anno,
+                bind,
+            } => {
+                if *exported {
+                    self.out.push_str("export ");
+                }
+
+                self.out.push_str("fun ");
+                self.expr(name, false);
+
+                if let Some(implicits) = implicits {
+                    self.out.push_str(" |");
+                    self.expr(implicits, false);
+                    self.out.push('|');
+                }
+
+                for arg in args {
+                    self.out.push(' ');
+                    self.expr(arg, false);
+                }
+
+                if let Some(anno) = anno {
+                    self.out.push_str(" : ");
+                    self.expr(anno, false);
+                }
+
+                self.bind(bind.as_ref());
+            }
+
+            DeclNode::ExternDecl {
+                header,
+                name,
+                args,
+                anno,
+            } => {
+                self.out.push_str("extern ");
+
+                if let Some(header) = header {
+                    self.out.push_str(&quote(header));
+                    self.out.push(' ');
+                }
+
+                self.out.push_str("fun ");
+                self.expr(name, false);
+
+                for arg in args {
+                    self.out.push(' ');
+                    self.expr(arg, false);
+                }
+
+                if let Some(anno) = anno {
+                    self.out.push_str(" : ");
+                    self.expr(anno, false);
+                }
+            }
+
+            DeclNode::ImportDecl { name } => {
+                self.out.push_str("import ");
+                self.expr(name, false);
+            }
+
+            DeclNode::TestDecl {
+                name_span, bind, ..
+            } => {
+                self.out.push_str("test ");
+                self.out.push_str(self.text(*name_span));
+                self.bind(bind.as_ref());
+            }
+        }
+    }
+
+    fn bind(&mut self, bind: Option<&Expr>) {
+        if let Some(bind) = bind {
+            self.body("=", bind, true);
+        }
+    }
+
+    /// Lay out the operator and body of a declaration or lambda. If the body is
+    /// in tail position, meaning nothing can follow it, an indented group is
+    /// kept as one.
+    fn body(&mut self, op: &str, body: &Expr, tail: bool) {
+        self.out.push(' ');
+        self.out.push_str(op);
+
+        match &body.node {
+            ExprNode::Group(inner) if tail && self.is_indented(body) => {
+                self.indent += 1;
+                self.newline();
+                self.expr(inner, true);
+                self.indent -= 1;
+            }
+
+            _ => {
+                self.out.push(' ');
+                self.expr(body, tail);
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr, tail: bool) {
+        match &expr.node {
+            ExprNode::Name(name) => self.out.push_str(name),
+            ExprNode::Num(_) | ExprNode::Str(_) | ExprNode::Invalid => {
+                self.out.push_str(self.text(expr.span))
+            }
+
+            ExprNode::Wildcard => self.out.push('?'),
+            ExprNode::Type => self.out.push_str("type"),
+            ExprNode::Text => self.out.push_str("text"),
+
+            // An indented group around some parentheses doesn't need another
+            // pair of them.
+            ExprNode::Group(inner)
+                if self.is_indented(expr) && matches!(inner.node, ExprNode::Group(_)) =>
+            {
+                self.expr(inner, false)
+            }
+
+            ExprNode::Group(inner) => {
+                self.out.push('(');
+                self.expr(inner, false);
+                self.out.push(')');
+            }
+
+            ExprNode::Range(_, a, b, None) => self.binary(a, " upto ", b),
+            ExprNode::Range(_, a, b, Some(step)) => {
+                self.binary(a, " upto ", b);
+                self.out.push_str(" by ");
+                self.expr(step, false);
+            }
+            ExprNode::Fun(_, a, b) => self.binary(a, " -> ", b),
+            ExprNode::BinOp(_, BinOp::Mul, a, b) => self.binary(a, " * ", b),
+            ExprNode::Tuple(a, b) => self.binary(a, ", ", b),
+
+            // Two minus signs in a row would start a comment.
+            ExprNode::Neg(inner) => {
+                self.out.push('-');
+                if matches!(inner.node, ExprNode::Neg(_)) {
+                    self.out.push(' ');
+                }
+
+                self.expr(inner, false);
+            }
+            ExprNode::Anno(a, b) => self.binary(a, ": ", b),
+
+            ExprNode::Lam(param, body) => {
+                self.expr(param, false);
+                self.body("=>", body, tail);
+            }
+
+            // The parser turns `f x => y` into `f (x => y)`, so a lambda as
+            // the argument is written the same way.
+            ExprNode::App(fun, arg) => {
+                self.expr(fun, false);
+                self.out.push(' ');
+                self.expr(arg, tail && matches!(arg.node, ExprNode::Lam(..)));
+            }
+
+            ExprNode::Inst(fun, arg) => {
+                self.expr(fun, false);
+                self.out.push_str(" |");
+                self.expr(arg, false);
+                self.out.push('|');
+            }
+        }
+    }
+
+    fn binary(&mut self, a: &Expr, op: &str, b: &Expr) {
+        self.expr(a, false);
+        self.out.push_str(op);
+        self.expr(b, false);
+    }
+
+    /// Returns `true` if the given group was made by indenting rather than with
+    /// parentheses.
+    fn is_indented(&self, group: &Expr) -> bool {
+        !self.source[group.span.start..].starts_with('(')
+    }
+}
+
+/// Quote some text, escaping it so that it is lexed as the same text again.
+fn quote(text: &str) -> String {
+    let mut res = String::from('"');
+    for c in text.chars() {
+        match c {
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            '\\' | '"' => {
+                res.push('\\');
+                res.push(c);
+            }
+
+            c => res.push(c),
+        }
+    }
+
+    res.push('"');
+    res
+}
@@ -0,0 +1,368 @@
+//! The formatter, which lays out source code in a single canonical style.
+//!
+//! The formatter works on the concrete syntax tree, together with the comments
+//! and blank lines the parser never sees. Top-level comments and single blank
+//! lines between declarations are kept where they are, while comments inside a
+//! declaration are moved to just before it, since the layout of the code
+//! inside a declaration may change completely. A comment at the end of the
+//! last line of a declaration is kept at the end of that line.
+//!
+//! Since groups made with parentheses and groups made by indenting are the
+//! same to the parser, the formatter is free to pick whichever fits best.
+//! Indented groups are only kept as the body of a declaration or a lambda,
+//! where nothing may follow them; everywhere else, parentheses are used.
+//! Formatting some already formatted code leaves it unchanged.
+
+mod layout;
+
+use zippy_common::message::{File, Messages, Span};
+
+use crate::lex::{lex_with_comments, Token};
+use crate::parse::parse_concrete;
+use crate::parse::tree::Decl;
+
+/// The number of spaces an indented group is indented by.
+const INDENT: usize = 4;
+
+/// Format some source text. Code with syntax errors can't be formatted without
+/// potentially losing parts of it, so the errors are returned instead.
+pub fn format(source: &str, file: File) -> Result<String, Messages> {
+    let (tokens, comments, mut msgs) = lex_with_comments(source, file);
+
+    // Groups made by indenting start and end at the line breaks around them,
+    // so only the tokens actually written in the code say where a
+    // declaration ends.
+    let ends = tokens
+        .iter()
+        .filter(|(token, span)| match token {
+            Token::GroupOpen | Token::GroupClose => {
+                matches!(source.as_bytes()[span.start], b'(' | b')')
+            }
+
+            Token::Delimit => false,
+            _ => true,
+        })
+        .map(|(_, span)| span.end)
+        .collect();
+
+    let (decls, parse_msgs) = parse_concrete(tokens, file);
+    msgs.merge(parse_msgs);
+
+    if !msgs.is_empty() {
+        return Err(msgs);
+    }
+
+    let items = Items {
+        source,
+        ends,
+        items: Vec::new(),
+        last_end: None,
+    }
+    .collect(decls, comments);
+
+    let mut formatter = Formatter::new(source);
+    for item in items.iter() {
+        formatter.item(item);
+    }
+
+    Ok(formatter.out)
+}
+
+/// A top-level comment or declaration, along with the trivia around it.
+struct Item {
+    node: ItemNode,
+
+    /// Whether this item was preceded by at least one blank line.
+    blank_before: bool,
+}
+
+enum ItemNode {
+    Comment(Span),
+    Decl {
+        decl: Decl,
+
+        /// Any comments inside the declaration.
+        inner: Vec<Span>,
+
+        /// A comment at the end of the last line of the declaration.
+        trailing: Option<Span>,
+    },
+}
+
+struct Items<'src> {
+    source: &'src str,
+
+    /// The end of every token written in the source, in order.
+    ends: Vec<usize>,
+
+    items: Vec<Item>,
+    last_end: Option<usize>,
+}
+
+impl Items<'_> {
+    fn collect(mut self, decls: Vec<Decl>, comments: Vec<Span>) -> Vec<Item> {
+        let mut comments = comments.into_iter().peekable();
+
+        for decl in decls {
+            let start = decl.span.start;
+            let end = self.end_of(decl.span);
+
+            while let Some(comment) = comments.next_if(|comment| comment.start < start) {
+                self.push(ItemNode::Comment(comment), comment.start, comment.end);
+            }
+
+            let mut inner = Vec::new();
+            while let Some(comment) = comments.next_if(|comment| comment.end <= end) {
+                inner.push(comment);
+            }
+
+            let source = self.source;
+            let trailing = comments.next_if(|comment| {
+                comment.start >= end && !source[end..comment.start].contains('\n')
+            });
+
+            let item_end = trailing.map(|comment| comment.end).unwrap_or(end);
+
+            let node = ItemNode::Decl {
+                decl,
+                inner,
+                trailing,
+            };
+
+            self.push(node, start, item_end);
+        }
+
+        for comment in comments {
+            self.push(ItemNode::Comment(comment), comment.start, comment.end);
+        }
+
+        self.items
+    }
+
+    fn push(&mut self, node: ItemNode, start: usize, end: usize) {
+        let blank_before = self
+            .last_end
+            .map(|last| self.source[last..start].matches('\n').count() > 1)
+            .unwrap_or(false);
+
+        self.items.push(Item { node, blank_before });
+        self.last_end = Some(end);
+    }
+
+    /// Get the end of the last token written within the given span.
+    fn end_of(&self, span: Span) -> usize {
+        let index = self.ends.partition_point(|end| *end <= span.end);
+        index
+            .checked_sub(1)
+            .map(|index| self.ends[index])
+            .unwrap_or(span.end)
+    }
+}
+
+struct Formatter<'src> {
+    source: &'src str,
+    out: String,
+    indent: usize,
+}
+
+impl<'src> Formatter<'src> {
+    fn new(source: &'src str) -> Self {
+        Self {
+            source,
+            out: String::new(),
+            indent: 0,
+        }
+    }
+
+    fn item(&mut self, item: &Item) {
+        if item.blank_before {
+            self.out.push('\n');
+        }
+
+        match &item.node {
+            ItemNode::Comment(span) => self.comment(*span),
+            ItemNode::Decl {
+                decl,
+                inner,
+                trailing,
+            } => {
+                for span in inner {
+                    self.comment(*span);
+                    self.out.push('\n');
+                }
+
+                self.decl(decl);
+
+                if let Some(span) = trailing {
+                    self.out.push(' ');
+                    self.comment(*span);
+                }
+            }
+        }
+
+        self.out.push('\n');
+    }
+
+    fn comment(&mut self, span: Span) {
+        self.out.push_str(self.text(span).trim_end());
+    }
+
+    /// Start a new line at the current indentation.
+    fn newline(&mut self) {
+        self.out.push('\n');
+        self.out
+            .extend(std::iter::repeat(' ').take(self.indent * INDENT));
+    }
+
+    /// Get the source text covered by the given span.
+    fn text(&self, span: Span) -> &'src str {
+        &self.source[span.start..span.end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format;
+
+    /// Check that `source` is formatted as `expected`, and that formatting it
+    /// again changes nothing.
+    fn check(source: &str, expected: &str) {
+        let once = format(source, 0).expect("source should parse");
+        assert_eq!(once, expected);
+
+        let twice = format(&once, 0).expect("formatted source should parse");
+        assert_eq!(twice, once);
+    }
+
+    #[test]
+    fn declarations() {
+        check(
+            concat!(
+                "import   other\n",
+                "type  Small=0 upto 10\n",
+                "let x :Small= 5\n",
+                "fun   add (x: Small) (y:Small) : 0 upto 20 = x\n",
+                "extern \"stdlib.h\" fun abs (x: 0 upto 100) : 0 upto 100\n",
+                "export fun twice (x: 0 upto 100) : 0 upto 200 = x * 2\n",
+                "test \"adds\"   = add 1 2\n",
+            ),
+            concat!(
+                "import other\n",
+                "type Small = 0 upto 10\n",
+                "let x: Small = 5\n",
+                "fun add (x: Small) (y: Small) : 0 upto 20 = x\n",
+                "extern \"stdlib.h\" fun abs (x: 0 upto 100) : 0 upto 100\n",
+                "export fun twice (x: 0 upto 100) : 0 upto 200 = x * 2\n",
+                "test \"adds\" = add 1 2\n",
+            ),
+        );
+    }
+
+    #[test]
+    fn implicits() {
+        check(
+            concat!(
+                "fun id |T| (x: T) = x\n",
+                "fun pair |T,U| (x: T, y: U) : T * U = (x, y)\n",
+                "let five = id|0 upto 10| 5\n",
+            ),
+            concat!(
+                "fun id |T| (x: T) = x\n",
+                "fun pair |T, U| (x: T, y: U) : T * U = (x, y)\n",
+                "let five = id |0 upto 10| 5\n",
+            ),
+        );
+    }
+
+    #[test]
+    fn tuples() {
+        check(
+            concat!(
+                "let (a, b) = (1, (2, 3))\n",
+                "let t : (0 upto 2) * ((0 upto 3) * (0 upto 4)) = (1,(2,3))\n",
+                "fun swap |T, U| ((x, y) : T * U) : U * T = (y, x)\n",
+            ),
+            concat!(
+                "let (a, b) = (1, (2, 3))\n",
+                "let t: (0 upto 2) * ((0 upto 3) * (0 upto 4)) = (1, (2, 3))\n",
+                "fun swap |T, U| ((x, y): T * U) : U * T = (y, x)\n",
+            ),
+        );
+    }
+
+    #[test]
+    fn annotations() {
+        check(
+            concat!(
+                "let x = (5 : 0 upto 10)\n",
+                "fun f (g: (0 upto 10 -> 0 upto 10) -> 0 upto 10) : 0 upto 10 = g ((x : 0 upto 10) => x)\n",
+                "let d : 0.0 upto 1.0 by 0.25 = (0.5 : 0.0 upto 1.0 by 0.25)\n",
+                "let n = - x\n",
+            ),
+            concat!(
+                "let x = (5: 0 upto 10)\n",
+                "fun f (g: (0 upto 10 -> 0 upto 10) -> 0 upto 10) : 0 upto 10 = g ((x: 0 upto 10) => x)\n",
+                "let d: 0.0 upto 1.0 by 0.25 = (0.5: 0.0 upto 1.0 by 0.25)\n",
+                "let n = -x\n",
+            ),
+        );
+    }
+
+    #[test]
+    fn indentation_groups() {
+        check(
+            concat!(
+                "fun f (x: 0 upto 10) : 0 upto 10 =\n",
+                "    g\n",
+                "        x\n",
+                "\n",
+                "let h = x =>\n",
+                "  f\n",
+                "    x\n",
+                "let j = f\n",
+                "    (g\n",
+                "        5)\n",
+            ),
+            concat!(
+                "fun f (x: 0 upto 10) : 0 upto 10 =\n",
+                "    g (x)\n",
+                "\n",
+                "let h = x =>\n",
+                "    f (x)\n",
+                "let j = f (g (5))\n",
+            ),
+        );
+    }
+
+    #[test]
+    fn comments() {
+        check(
+            concat!(
+                "-- A comment at the top.\n",
+                "\n",
+                "\n",
+                "-- Another one after two blank lines.\n",
+                "let x = 5 -- at the end of the line\n",
+                "--- Documentation for y.\n",
+                "let y =\n",
+                "    -- inside the declaration\n",
+                "    x\n",
+                "\n",
+                "\n",
+                "-- trailing comment\n",
+            ),
+            concat!(
+                "-- A comment at the top.\n",
+                "\n",
+                "-- Another one after two blank lines.\n",
+                "let x = 5 -- at the end of the line\n",
+                "--- Documentation for y.\n",
+                "-- inside the declaration\n",
+                "let y =\n",
+                "    x\n",
+                "\n",
+                "-- trailing comment\n",
+            ),
+        );
+    }
+}
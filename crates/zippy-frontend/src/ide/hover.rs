@@ -0,0 +1,78 @@
+//! Finds the type of the innermost expression or pattern covering some offset.
+
+use zippy_common::hir2::{Expr, ExprNode, Pat, PatNode, Type, ValueDef};
+use zippy_common::message::Span;
+
+use super::covers;
+
+pub struct TypeFinder {
+    at: Span,
+    pub found: Option<(Span, Type)>,
+}
+
+impl TypeFinder {
+    pub fn new(at: Span) -> Self {
+        Self { at, found: None }
+    }
+
+    pub fn find_value_def(&mut self, def: &ValueDef) {
+        if self.covers(def.span) {
+            self.pat(&def.pat);
+            self.expr(&def.body);
+        }
+    }
+
+    fn covers(&self, span: Span) -> bool {
+        covers(span, self.at)
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        if !self.covers(expr.span) {
+            return;
+        }
+
+        self.found = Some((expr.span, expr.data.clone()));
+
+        match &expr.node {
+            ExprNode::Lam(param, body) => {
+                self.pat(param);
+                self.expr(body);
+            }
+
+            ExprNode::App(a, b) | ExprNode::Tuple(a, b) => {
+                self.expr(a);
+                self.expr(b);
+            }
+
+            ExprNode::Neg(expr) | ExprNode::Anno(expr, _, _) | ExprNode::Coerce(expr, _) => {
+                self.expr(expr)
+            }
+
+            ExprNode::Name(_)
+            | ExprNode::Num(_)
+            | ExprNode::Str(_)
+            | ExprNode::Inst(..)
+            | ExprNode::Extern(_)
+            | ExprNode::Hole
+            | ExprNode::Invalid => {}
+        }
+    }
+
+    fn pat(&mut self, pat: &Pat) {
+        if !self.covers(pat.span) {
+            return;
+        }
+
+        self.found = Some((pat.span, pat.data.clone()));
+
+        match &pat.node {
+            PatNode::Tuple(a, b) => {
+                self.pat(a);
+                self.pat(b);
+            }
+
+            PatNode::Anno(pat, _) | PatNode::Coerce(pat, _) => self.pat(pat),
+            PatNode::Name(_) | PatNode::Wildcard | PatNode::Invalid => {}
+        }
+    }
+}
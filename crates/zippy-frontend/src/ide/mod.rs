@@ -0,0 +1,277 @@
+//! Queries used by editor tooling, like the language server. These all work on
+//! a [`Project`], and only ever run the parts of the frontend they need, so
+//! that salsa can reuse as much work as possible between edits. Positions are
+//! given as a file together with a byte offset into that file.
+
+mod hover;
+mod occurrences;
+
+use std::collections::HashMap;
+
+use zippy_common::hir2::{pretty_type, PrettyMap, Type, TypeckResult};
+use zippy_common::message::{Diagnostic, File, Span};
+use zippy_common::names2::{Name, NamePart};
+
+use self::hover::TypeFinder;
+use self::occurrences::{occurrences, Occurrence};
+use crate::dump::{pat_names as typed_names, pretty_kind};
+use crate::{kick, resolve, resolved, tyck2, Db, MessageAccumulator, Project};
+
+/// The kind of a top-level definition.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SymbolKind {
+    Value,
+    Type,
+    Test,
+}
+
+/// A top-level definition in a program.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+
+    /// The span of the entire definition.
+    pub span: Span,
+
+    /// The span of the defined name.
+    pub name_span: Span,
+}
+
+/// A top-level definition along with its documentation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Documented {
+    pub name: String,
+    pub kind: SymbolKind,
+
+    /// The inferred type of a value, or the kind of a type.
+    pub ty: String,
+
+    /// The documentation comment before the definition, if any.
+    pub doc: Option<String>,
+
+    /// The span of the entire definition.
+    pub span: Span,
+}
+
+/// Get every message produced while checking the given project.
+pub fn diagnostics(db: &dyn Db, project: Project) -> Vec<Diagnostic> {
+    let decls = resolve::resolve(db, project);
+
+    let _ = kick::kindck(db, decls);
+    let _ = tyck2::typeck(db, decls);
+
+    // Resolving a project lexes and parses every file, so the messages from
+    // those passes are included here.
+    let accumulated = [
+        resolve::resolve::accumulated::<MessageAccumulator>(db, project),
+        kick::kindck::accumulated::<MessageAccumulator>(db, decls),
+        tyck2::typeck::accumulated::<MessageAccumulator>(db, decls),
+    ];
+
+    accumulated.into_iter().flatten().collect()
+}
+
+/// Get the span and the pretty-printed type of the innermost expression or
+/// pattern at the given offset.
+pub fn hover(db: &dyn Db, project: Project, file: File, offset: usize) -> Option<(Span, String)> {
+    let decls = resolve::resolve(db, project);
+    let tyckres = tyck2::typeck(db, decls);
+
+    let common = common_db(db);
+    let values = tyckres.decls(common).values(common);
+
+    let mut finder = TypeFinder::new(Span::new(file, offset, offset));
+    for def in values.iter() {
+        finder.find_value_def(def);
+    }
+
+    let (span, ty) = finder.found?;
+    Some((span, pretty(db, tyckres, &ty)))
+}
+
+/// Get the pretty-printed type of the body of the top-level definition at the
+/// given offset.
+pub fn definition_type(db: &dyn Db, project: Project, file: File, offset: usize) -> Option<String> {
+    let decls = resolve::resolve(db, project);
+    let tyckres = tyck2::typeck(db, decls);
+
+    let common = common_db(db);
+    let at = Span::new(file, offset, offset);
+    let def = tyckres
+        .decls(common)
+        .values(common)
+        .iter()
+        .find(|def| covers(def.span, at))?;
+
+    Some(pretty(db, tyckres, &def.body.data))
+}
+
+/// Get the span of the declaration of the name at the given offset.
+pub fn definition(db: &dyn Db, project: Project, file: File, offset: usize) -> Option<Span> {
+    let occurrences = occurrences(db, resolve::resolve(db, project));
+    let name = name_at(&occurrences, Span::new(file, offset, offset))?;
+
+    occurrences
+        .iter()
+        .find(|occurrence| occurrence.declaration && occurrence.name == name)
+        .map(|occurrence| occurrence.span)
+}
+
+/// Get the span of every occurrence of the name at the given offset,
+/// including its declaration.
+pub fn references(db: &dyn Db, project: Project, file: File, offset: usize) -> Vec<Span> {
+    let occurrences = occurrences(db, resolve::resolve(db, project));
+    let Some(name) = name_at(&occurrences, Span::new(file, offset, offset)) else {
+        return Vec::new();
+    };
+
+    occurrences
+        .iter()
+        .filter(|occurrence| occurrence.name == name)
+        .map(|occurrence| occurrence.span)
+        .collect()
+}
+
+/// Get every named top-level definition in the given file.
+pub fn symbols(db: &dyn Db, project: Project, file: File) -> Vec<Symbol> {
+    let decls = resolve::resolve(db, project);
+    let mut symbols = Vec::new();
+
+    let values = decls.values(db).iter().map(|def| (&def.pat, def.span));
+    let types = decls.types(db).iter().map(|def| (&def.pat, def.span));
+    let defs = values
+        .map(|def| (def, SymbolKind::Value))
+        .chain(types.map(|def| (def, SymbolKind::Type)))
+        .filter(|((_, span), _)| span.file == file);
+
+    for ((pat, span), kind) in defs {
+        let mut names = Vec::new();
+        pat_names(pat, &mut names);
+
+        for (name, name_span) in names {
+            // Tests are named by their description rather than their
+            // generated name.
+            if let Some(test) = decls.tests(db).iter().find(|test| test.name == name) {
+                symbols.push(Symbol {
+                    name: test.description.clone(),
+                    kind: SymbolKind::Test,
+                    span,
+                    name_span,
+                });
+            } else if let NamePart::Source(text) = name.name(common_db(db)) {
+                symbols.push(Symbol {
+                    name: text.clone(),
+                    kind,
+                    span,
+                    name_span,
+                });
+            }
+        }
+    }
+
+    symbols
+}
+
+/// Get every named top-level value and type in the given file along with its
+/// documentation, in the order they are defined. Tests are left out.
+pub fn documentation(db: &dyn Db, project: Project, file: File) -> Vec<Documented> {
+    let decls = resolve::resolve(db, project);
+    let tyckres = tyck2::typeck(db, decls);
+    let kinds = kick::kindck(db, decls);
+
+    let common = common_db(db);
+    let mut types = Vec::new();
+    for def in tyckres.decls(common).values(common).iter() {
+        typed_names(&def.pat, &mut types);
+    }
+
+    let types: HashMap<_, _> = types.into_iter().collect();
+
+    let values = decls
+        .values(db)
+        .iter()
+        .map(|def| (&def.pat, def.span, &def.doc, SymbolKind::Value));
+    let type_defs = decls
+        .types(db)
+        .iter()
+        .map(|def| (&def.pat, def.span, &def.doc, SymbolKind::Type));
+
+    let mut defs: Vec<_> = values
+        .chain(type_defs)
+        .filter(|(_, span, _, _)| span.file == file)
+        .collect();
+
+    defs.sort_by_key(|(_, span, _, _)| span.start);
+
+    let mut documented = Vec::new();
+    for (pat, span, doc, kind) in defs {
+        let mut names = Vec::new();
+        pat_names(pat, &mut names);
+
+        for (name, _) in names {
+            if decls.tests(db).iter().any(|test| test.name == name) {
+                continue;
+            }
+
+            // Expressions lifted out of types don't have a name of their own.
+            let NamePart::Source(text) = name.name(common) else {
+                continue;
+            };
+
+            let ty = match kind {
+                SymbolKind::Type => kinds.kinds(db).get(&name).map(pretty_kind),
+                _ => types.get(&name).map(|ty| pretty(db, tyckres, ty)),
+            };
+
+            documented.push(Documented {
+                name: text.clone(),
+                kind,
+                ty: ty.unwrap_or_else(|| "?".into()),
+                doc: doc.clone(),
+                span,
+            });
+        }
+    }
+
+    documented
+}
+
+/// Find the name whose occurrence covers the given position.
+fn name_at(occurrences: &[Occurrence], at: Span) -> Option<Name> {
+    occurrences
+        .iter()
+        .find(|occurrence| covers(occurrence.span, at))
+        .map(|occurrence| occurrence.name)
+}
+
+/// Returns `true` if the span `outer` contains the span `inner`.
+fn covers(outer: Span, inner: Span) -> bool {
+    outer.file == inner.file && outer.start <= inner.start && inner.end <= outer.end
+}
+
+/// Collect every name bound by the given pattern.
+fn pat_names(pat: &resolved::Pat, into: &mut Vec<(Name, Span)>) {
+    match &pat.node {
+        resolved::PatNode::Name(name) => into.push((*name, pat.span)),
+        resolved::PatNode::Tuple(a, b) => {
+            pat_names(a, into);
+            pat_names(b, into);
+        }
+
+        resolved::PatNode::Anno(pat, _) => pat_names(pat, into),
+        resolved::PatNode::Wildcard | resolved::PatNode::Invalid => {}
+    }
+}
+
+/// Pretty-print a type using the substitution from typechecking.
+fn pretty(db: &dyn Db, tyckres: TypeckResult, ty: &Type) -> String {
+    let common = common_db(db);
+    let subst = tyckres.subst(common);
+    let subst = subst.iter().map(|(var, (_, ty))| (*var, ty)).collect();
+    pretty_type(common, &subst, &mut PrettyMap::new(), ty)
+}
+
+fn common_db(db: &dyn Db) -> &dyn zippy_common::Db {
+    <dyn Db as salsa::DbWithJar<zippy_common::Jar>>::as_jar_db(db)
+}
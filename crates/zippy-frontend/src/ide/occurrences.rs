@@ -0,0 +1,134 @@
+//! Finds every place a name is mentioned in the resolved program.
+
+use zippy_common::message::Span;
+use zippy_common::names2::{Name, NamePart};
+
+use crate::resolved::{Decls, Expr, ExprNode, Pat, PatNode, Type, TypeNode};
+use crate::Db;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Occurrence {
+    pub name: Name,
+    pub span: Span,
+
+    /// `true` if this occurrence declares the name.
+    pub declaration: bool,
+}
+
+pub fn occurrences(db: &dyn Db, decls: Decls) -> Vec<Occurrence> {
+    let mut finder = Finder {
+        db,
+        occurrences: Vec::new(),
+    };
+
+    for def in decls.values(db).iter() {
+        finder.pat(&def.pat);
+        finder.ty(&def.anno);
+        finder.expr(&def.bind);
+    }
+
+    for def in decls.types(db).iter() {
+        finder.pat(&def.pat);
+        finder.ty(&def.anno);
+        finder.ty(&def.bind);
+    }
+
+    finder.occurrences
+}
+
+struct Finder<'a> {
+    db: &'a dyn Db,
+    occurrences: Vec<Occurrence>,
+}
+
+impl Finder<'_> {
+    fn add(&mut self, name: Name, span: Span, declaration: bool) {
+        // Names of lifted range bounds aren't written in the source, so they
+        // shouldn't show up anywhere.
+        let common = <dyn Db as salsa::DbWithJar<zippy_common::Jar>>::as_jar_db(self.db);
+        if let NamePart::Spanned(_) = name.name(common) {
+            return;
+        }
+
+        self.occurrences.push(Occurrence {
+            name,
+            span,
+            declaration,
+        });
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match &expr.node {
+            ExprNode::Name(name) => self.add(*name, expr.span, false),
+
+            ExprNode::Lam(param, body) => {
+                self.pat(param);
+                self.expr(body);
+            }
+
+            ExprNode::App(a, b) | ExprNode::Tuple(a, b) => {
+                self.expr(a);
+                self.expr(b);
+            }
+
+            ExprNode::Inst(expr, args) => {
+                self.expr(expr);
+                for arg in args {
+                    self.ty(arg);
+                }
+            }
+
+            ExprNode::Neg(expr) => self.expr(expr),
+
+            ExprNode::Anno(expr, ty) => {
+                self.expr(expr);
+                self.ty(ty);
+            }
+
+            ExprNode::Num(_)
+            | ExprNode::Str(_)
+            | ExprNode::Extern(_)
+            | ExprNode::Hole
+            | ExprNode::Invalid => {}
+        }
+    }
+
+    fn pat(&mut self, pat: &Pat) {
+        match &pat.node {
+            PatNode::Name(name) => self.add(*name, pat.span, true),
+
+            PatNode::Tuple(a, b) => {
+                self.pat(a);
+                self.pat(b);
+            }
+
+            PatNode::Anno(pat, ty) => {
+                self.pat(pat);
+                self.ty(ty);
+            }
+
+            PatNode::Wildcard | PatNode::Invalid => {}
+        }
+    }
+
+    fn ty(&mut self, ty: &Type) {
+        match &ty.node {
+            TypeNode::Name(name) => self.add(*name, ty.span, false),
+
+            TypeNode::Fun(t, u) | TypeNode::Product(t, u) => {
+                self.ty(t);
+                self.ty(u);
+            }
+
+            // The bounds of a range have been lifted into their own
+            // definitions, which are visited separately.
+            TypeNode::Range(..) => {}
+
+            TypeNode::Type
+            | TypeNode::Number
+            | TypeNode::Text
+            | TypeNode::Wildcard
+            | TypeNode::Invalid => {}
+        }
+    }
+}
@@ -33,7 +33,7 @@ impl Kinder<'_> {
                 Kind::Type
             }
 
-            TypeNode::Number => Kind::Type,
+            TypeNode::Number | TypeNode::Text => Kind::Type,
             TypeNode::Wildcard | TypeNode::Invalid => Kind::Var(self.fresh()),
 
             TypeNode::Type => todo!(),
@@ -1,5 +1,7 @@
 mod token;
 
+use std::str::CharIndices;
+
 use log::{debug, info};
 use logos::Logos;
 
@@ -14,7 +16,13 @@ pub enum Token {
     Fun,
     Let,
     Type,
+    Import,
+    Test,
+    Export,
+    Extern,
     Upto,
+    By,
+    Text,
 
     GroupOpen,
     GroupClose,
@@ -27,12 +35,15 @@ pub enum Token {
     Question,
     Comma,
     Star,
+    Minus,
 
     Equal,
     Colon,
 
     Name(String),
     Number(String),
+    String(String),
+    Char(char),
 
     Invalid,
 }
@@ -41,6 +52,11 @@ pub enum Token {
 pub struct Tokens {
     #[return_ref]
     pub tokens: Vec<(Token, Span)>,
+
+    /// The text of every documentation comment, along with the offset of the
+    /// token following it.
+    #[return_ref]
+    pub docs: Vec<(usize, String)>,
     pub file: File,
 }
 
@@ -59,7 +75,28 @@ pub fn lex(db: &dyn crate::Db, source: SourceProgram) -> Tokens {
 
     debug!("done lexing {file}");
 
-    Tokens::new(db, lexer.res, file)
+    Tokens::new(db, lexer.res, lexer.docs, file)
+}
+
+/// Lex some source text outside of the database, also returning the span of
+/// every comment, which are otherwise thrown away. This is used by tools like
+/// the formatter, which need to keep the comments around.
+pub fn lex_with_comments(source: &str, file: File) -> (Vec<(Token, Span)>, Vec<Span>, Messages) {
+    let mut lexer = Lexer::new(source, file);
+    lexer.lex();
+
+    (lexer.res, lexer.comments, lexer.msgs)
+}
+
+/// Returns `true` if the given source text stops in the middle of something,
+/// such as right after an `=` or inside an unclosed parenthesis. This is used
+/// to decide whether to read a continuation line when reading code
+/// interactively.
+pub fn is_incomplete(source: &str) -> bool {
+    let mut lexer = Lexer::new(source, 0);
+    lexer.lex();
+
+    lexer.parens > 0 || (lexer.last_span.is_some() && !lexer.delimit_after)
 }
 
 impl Token {
@@ -68,26 +105,38 @@ impl Token {
             Self::Fun
             | Self::Let
             | Self::Type
+            | Self::Import
+            | Self::Test
+            | Self::Export
+            | Self::Extern
             | Self::Upto
+            | Self::By
             | Self::GroupOpen
             | Self::Pipe
             | Self::MinArrow
             | Self::EqArrow
             | Self::Comma
             | Self::Star
+            | Self::Minus
             | Self::Equal
             | Self::Colon
             | Self::Delimit => false,
 
-            Self::GroupClose | Self::Question | Self::Name(_) | Self::Number(_) | Self::Invalid => {
-                true
-            }
+            Self::GroupClose
+            | Self::Question
+            | Self::Text
+            | Self::Name(_)
+            | Self::Number(_)
+            | Self::String(_)
+            | Self::Char(_)
+            | Self::Invalid => true,
         }
     }
 
     fn group_before(&self) -> bool {
         match self {
             Self::Upto
+            | Self::By
             | Self::GroupClose
             | Self::Pipe
             | Self::Delimit
@@ -101,10 +150,18 @@ impl Token {
             Self::Fun
             | Self::Let
             | Self::Type
+            | Self::Import
+            | Self::Test
+            | Self::Export
+            | Self::Extern
             | Self::GroupOpen
             | Self::Question
+            | Self::Minus
+            | Self::Text
             | Self::Name(_)
             | Self::Number(_)
+            | Self::String(_)
+            | Self::Char(_)
             | Self::Invalid => true,
         }
     }
@@ -114,12 +171,20 @@ struct Lexer<'src> {
     lex: logos::SpannedIter<'src, FreeToken<'src>>,
     file: File,
     res: Vec<(Token, Span)>,
+    comments: Vec<Span>,
     msgs: Messages,
 
+    /// Documentation comments, and the lines of the one being read.
+    docs: Vec<(usize, String)>,
+    doc_lines: Vec<&'src str>,
+
     indents: Vec<usize>,
     delimit_after: bool,
     last_newline: Option<(usize, Span)>,
 
+    /// The number of explicit parentheses which are currently open.
+    parens: usize,
+
     last_span: Option<Span>,
 }
 
@@ -129,12 +194,18 @@ impl<'src> Lexer<'src> {
             lex: FreeToken::lexer(src).spanned(),
             file,
             res: Vec::new(),
+            comments: Vec::new(),
             msgs: Messages::new(),
 
+            docs: Vec::new(),
+            doc_lines: Vec::new(),
+
             indents: Vec::new(),
             delimit_after: false,
             last_newline: None,
 
+            parens: 0,
+
             last_span: None,
         }
     }
@@ -142,9 +213,11 @@ impl<'src> Lexer<'src> {
     fn lex(&mut self) {
         while self.dispatch() {}
 
-        let span = self.last_span.unwrap();
-        self.res
-            .extend(std::iter::repeat_with(|| (Token::GroupClose, span)).take(self.indents.len()));
+        if let Some(span) = self.last_span {
+            self.res.extend(
+                std::iter::repeat_with(|| (Token::GroupClose, span)).take(self.indents.len()),
+            );
+        }
     }
 
     fn dispatch(&mut self) -> bool {
@@ -154,25 +227,67 @@ impl<'src> Lexer<'src> {
                 FreeToken::Fun => Token::Fun,
                 FreeToken::Let => Token::Let,
                 FreeToken::Type => Token::Type,
+                FreeToken::Import => Token::Import,
+                FreeToken::Test => Token::Test,
+                FreeToken::Export => Token::Export,
+                FreeToken::Extern => Token::Extern,
                 FreeToken::Upto => Token::Upto,
-                FreeToken::LParen => Token::GroupOpen,
-                FreeToken::RParen => Token::GroupClose,
+                FreeToken::By => Token::By,
+                FreeToken::Text => Token::Text,
+                FreeToken::LParen => {
+                    self.parens += 1;
+                    Token::GroupOpen
+                }
+
+                FreeToken::RParen => {
+                    self.parens = self.parens.saturating_sub(1);
+                    Token::GroupClose
+                }
+
                 FreeToken::Pipe => Token::Pipe,
                 FreeToken::MinArrow => Token::MinArrow,
                 FreeToken::EqArrow => Token::EqArrow,
                 FreeToken::Question => Token::Question,
                 FreeToken::Comma => Token::Comma,
                 FreeToken::Star => Token::Star,
+                FreeToken::Minus => Token::Minus,
                 FreeToken::Equal => Token::Equal,
                 FreeToken::Colon => Token::Colon,
                 FreeToken::Name(name) => Token::Name(name.into()),
-                FreeToken::DecNumber(num) => Token::Number(num.into()),
+                FreeToken::Number(num) => self.number(span, num),
+                FreeToken::String(text) => self.string(span, &text[1..text.len() - 1]),
+                FreeToken::Char(text) => self.char(span, &text[1..text.len() - 1]),
+
+                FreeToken::UnterminatedString(_) => {
+                    self.msgs.at(span).lex_unterminated_literal('"');
+                    Token::Invalid
+                }
+
+                FreeToken::UnterminatedChar(_) => {
+                    self.msgs.at(span).lex_unterminated_literal('\'');
+                    Token::Invalid
+                }
 
                 FreeToken::Newline(indent) => {
                     self.last_newline = Some((indent, span));
                     return true;
                 }
 
+                // Comments don't affect the layout, so a comment on its own
+                // line doesn't open or close any groups. Documentation comments
+                // start with exactly three dashes, and belong to whatever
+                // comes after them.
+                FreeToken::Comment(text) => {
+                    if let Some(line) = text.strip_prefix("---") {
+                        if !line.starts_with('-') {
+                            self.doc_lines.push(line.strip_prefix(' ').unwrap_or(line));
+                        }
+                    }
+
+                    self.comments.push(span);
+                    return true;
+                }
+
                 FreeToken::Error => {
                     self.msgs.at(span).lex_invalid();
                     Token::Invalid
@@ -181,6 +296,12 @@ impl<'src> Lexer<'src> {
 
             self.last_span = Some(span);
 
+            if !self.doc_lines.is_empty() {
+                let doc = self.doc_lines.join("\n");
+                self.docs.push((span.start, doc));
+                self.doc_lines.clear();
+            }
+
             self.handle_newline(tok.group_before());
             self.delimit_after = tok.delimit_after();
             self.res.push((tok, span));
@@ -191,6 +312,99 @@ impl<'src> Lexer<'src> {
         }
     }
 
+    /// Check that every digit of a number literal is valid for its base, and
+    /// that there is at least one of them. Only decimal numbers may have a
+    /// fractional part.
+    fn number(&mut self, span: Span, text: &str) -> Token {
+        let (prefix, radix) = match text.get(..2) {
+            Some("0x") => ("0x", 16),
+            Some("0o") => ("0o", 8),
+            Some("0b") => ("0b", 2),
+            _ => ("", 10),
+        };
+
+        let digits = &text[prefix.len()..];
+        for (at, c) in digits.char_indices() {
+            if c.is_digit(radix) || c == '_' || c == '\'' || (c == '.' && radix == 10) {
+                continue;
+            }
+
+            let start = span.start + prefix.len() + at;
+            let span = Span::new(self.file, start, start + c.len_utf8());
+            self.msgs.at(span).lex_invalid_digit(c, radix);
+            return Token::Invalid;
+        }
+
+        if !digits.chars().any(|c| c.is_digit(radix)) {
+            self.msgs.at(span).lex_missing_digits(prefix);
+            return Token::Invalid;
+        }
+
+        Token::Number(text.into())
+    }
+
+    /// Lex the text between the quotes of a string literal.
+    fn string(&mut self, span: Span, text: &str) -> Token {
+        match self.unescape(span.start + 1, text) {
+            Some(text) => Token::String(text),
+            None => Token::Invalid,
+        }
+    }
+
+    /// Lex the text between the quotes of a character literal, which must be
+    /// exactly one character once escape sequences are replaced.
+    fn char(&mut self, span: Span, text: &str) -> Token {
+        let Some(text) = self.unescape(span.start + 1, text) else {
+            return Token::Invalid;
+        };
+
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Token::Char(c),
+            _ => {
+                self.msgs.at(span).lex_invalid_char_literal();
+                Token::Invalid
+            }
+        }
+    }
+
+    /// Replace the escape sequences in the text of a literal starting at the
+    /// given offset. Every invalid escape sequence is reported, in which case
+    /// nothing is returned.
+    fn unescape(&mut self, start: usize, text: &str) -> Option<String> {
+        let mut res = String::with_capacity(text.len());
+        let mut valid = true;
+        let mut chars = text.char_indices();
+
+        while let Some((at, c)) = chars.next() {
+            if c != '\\' {
+                res.push(c);
+                continue;
+            }
+
+            let escaped = match chars.next() {
+                Some((_, 'n')) => Some('\n'),
+                Some((_, 'r')) => Some('\r'),
+                Some((_, 't')) => Some('\t'),
+                Some((_, c @ ('\\' | '"' | '\''))) => Some(c),
+                Some((_, 'u')) => unicode_escape(&mut chars),
+                _ => None,
+            };
+
+            match escaped {
+                Some(c) => res.push(c),
+                None => {
+                    let end = chars.clone().next().map_or(text.len(), |(end, _)| end);
+                    let span = Span::new(self.file, start + at, start + end);
+                    self.msgs.at(span).lex_invalid_escape();
+                    valid = false;
+                }
+            }
+        }
+
+        valid.then_some(res)
+    }
+
     fn handle_newline(&mut self, group_before: bool) {
         if let Some((indent, span)) = self.last_newline {
             let top = self.indents.last().cloned().unwrap_or(0);
@@ -221,3 +435,115 @@ impl<'src> Lexer<'src> {
         self.last_newline = None;
     }
 }
+
+/// Read the rest of a `\u{...}` escape sequence, which gives the code point of
+/// a character in hexadecimal. Null characters aren't allowed, since text is
+/// null-terminated in the generated code.
+fn unicode_escape(chars: &mut CharIndices) -> Option<char> {
+    if chars.next()?.1 != '{' {
+        return None;
+    }
+
+    let mut code: u32 = 0;
+    let mut digits = 0;
+    loop {
+        match chars.next()?.1 {
+            '}' if digits > 0 => break,
+            c => {
+                code = code.checked_mul(16)?.checked_add(c.to_digit(16)?)?;
+                digits += 1;
+            }
+        }
+    }
+
+    char::from_u32(code).filter(|c| *c != '\0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lex_with_comments, Token};
+
+    /// Lex some source text, giving back its tokens and the codes of any
+    /// messages.
+    fn lex(source: &str) -> (Vec<Token>, Vec<String>) {
+        let (tokens, _, messages) = lex_with_comments(source, 0);
+        let tokens = tokens.into_iter().map(|(token, _)| token).collect();
+        let codes = messages
+            .msgs
+            .into_iter()
+            .filter_map(|msg| msg.code)
+            .collect();
+        (tokens, codes)
+    }
+
+    #[test]
+    fn numbers() {
+        for source in [
+            "0x1F",
+            "0o17",
+            "0b101",
+            "1_000",
+            "1'000",
+            "0x_ff",
+            "0b1010'0101",
+            "0.25",
+        ] {
+            let expected = (vec![Token::Number(source.into())], Vec::new());
+            assert_eq!(lex(source), expected, "{source}");
+        }
+    }
+
+    #[test]
+    fn numbers_without_digits() {
+        for source in ["0x", "0o", "0b", "0x_", "0b''"] {
+            let expected = (vec![Token::Invalid], vec!["EL04".into()]);
+            assert_eq!(lex(source), expected, "{source}");
+        }
+    }
+
+    #[test]
+    fn digits_invalid_for_base() {
+        for source in ["0b102", "0o78", "0xfg", "12ab", "0x1.5", "0b1.0"] {
+            let expected = (vec![Token::Invalid], vec!["EL05".into()]);
+            assert_eq!(lex(source), expected, "{source}");
+        }
+    }
+
+    #[test]
+    fn escapes() {
+        let cases = [
+            (r#""a\nb""#, "a\nb"),
+            (r#""\t\r\\""#, "\t\r\\"),
+            (r#""\"\'""#, "\"'"),
+            (r#""\u{41}\u{1F600}""#, "A\u{1F600}"),
+        ];
+
+        for (source, text) in cases {
+            let expected = (vec![Token::String(text.into())], Vec::new());
+            assert_eq!(lex(source), expected, "{source}");
+        }
+
+        assert_eq!(lex(r"'\n'"), (vec![Token::Char('\n')], Vec::new()));
+    }
+
+    #[test]
+    fn invalid_escapes() {
+        for source in [
+            r#""\q""#,
+            r#""\u{}""#,
+            r#""\u{0}""#,
+            r#""\u{110000}""#,
+            r#""\u41""#,
+        ] {
+            let expected = (vec![Token::Invalid], vec!["EL02".into()]);
+            assert_eq!(lex(source), expected, "{source}");
+        }
+    }
+
+    #[test]
+    fn invalid_literals() {
+        assert_eq!(lex(r#""abc"#), (vec![Token::Invalid], vec!["EL01".into()]));
+        assert_eq!(lex("'ab'"), (vec![Token::Invalid], vec!["EL03".into()]));
+        assert_eq!(lex("''"), (vec![Token::Invalid], vec!["EL03".into()]));
+    }
+}
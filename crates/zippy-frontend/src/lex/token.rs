@@ -11,9 +11,27 @@ pub enum FreeToken<'src> {
     #[token("type")]
     Type,
 
+    #[token("import")]
+    Import,
+
+    #[token("test")]
+    Test,
+
+    #[token("export")]
+    Export,
+
+    #[token("extern")]
+    Extern,
+
     #[token("upto")]
     Upto,
 
+    #[token("by")]
+    By,
+
+    #[token("text")]
+    Text,
+
     #[token("(")]
     LParen,
 
@@ -38,6 +56,9 @@ pub enum FreeToken<'src> {
     #[token("*")]
     Star,
 
+    #[token("-")]
+    Minus,
+
     #[token("=")]
     Equal,
 
@@ -47,14 +68,35 @@ pub enum FreeToken<'src> {
     #[regex(r"[a-zA-Z][a-zA-Z0-9_']*")]
     Name(&'src str),
 
-    #[regex(r"[0-9][0-9_']*")]
-    DecNumber(&'src str),
+    /// A number literal, which may start with `0x`, `0o` or `0b`, or have a
+    /// fractional part. Letters are included so that the lexer can report
+    /// malformed literals as a whole, rather than as a number followed by a
+    /// name.
+    #[regex(r"[0-9][0-9a-zA-Z_']*(\.[0-9][0-9a-zA-Z_']*)?")]
+    Number(&'src str),
+
+    /// A string literal, along with its quotes. Escape sequences are replaced
+    /// by the lexer, which reports any invalid ones.
+    #[regex(r#""([^"\\\n\r]|\\[^\n\r])*""#)]
+    String(&'src str),
+
+    /// A string literal missing its closing quote.
+    #[regex(r#""([^"\\\n\r]|\\[^\n\r])*"#)]
+    UnterminatedString(&'src str),
+
+    #[regex(r"'([^'\\\n\r]|\\[^\n\r])*'")]
+    Char(&'src str),
+
+    #[regex(r"'([^'\\\n\r]|\\[^\n\r])*")]
+    UnterminatedChar(&'src str),
 
     #[regex(r"[\n\r][ \t]*", |lex| lex.slice().len() - 1)]
     Newline(usize),
 
+    #[regex(r"--[^\n\r]*")]
+    Comment(&'src str),
+
     #[error]
     #[regex(r"[ \t\v\f]+", logos::skip)]
-    #[regex(r"--[^\n\r]*", logos::skip)]
     Error,
 }
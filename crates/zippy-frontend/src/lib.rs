@@ -1,43 +1,221 @@
 pub mod components;
 pub mod definitions;
+pub mod fmt;
+pub mod ide;
 pub mod kick;
 pub mod lex;
 pub mod parse;
 pub mod resolve;
 pub mod tyck2;
 
+mod bridge;
+mod dump;
 mod resolved;
 mod unresolved;
 
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use log::info;
 use salsa::DbWithJar;
+use zippy_common::message::{File, Messages, Span};
 use zippy_common::names::{Name, Names};
+use zippy_common::names2::{self, NamePart};
 use zippy_common::thir::TypeckResult;
-use zippy_common::Driver;
+use zippy_common::{Driver, IrOutput, Pass, Stat};
+
+use self::bridge::Bridged;
 
 #[derive(Debug)]
 pub struct ParseResult {
     pub checked: TypeckResult,
     pub names: Names,
     pub entry: Option<Name>,
+    pub tests: Vec<Test>,
+
+    /// Every function declared with `export fun`.
+    pub exports: Vec<Name>,
 }
 
-pub fn parse(driver: &mut impl Driver, source: String, file: usize) -> ParseResult {
+/// A test declared with `test "description" = body`. The body of the test is
+/// the value with the given name.
+#[derive(Clone, Debug)]
+pub struct Test {
+    pub name: Name,
+    pub description: String,
+    pub span: Span,
+}
+
+pub fn parse(driver: &mut impl Driver, source: String, file: File) -> ParseResult {
     let db = Database::default();
-    let program = SourceProgram::new(&db, source, file);
+    let root = SourceProgram::new(&db, source, file);
+
+    let (files, imports, times) = collect_files(&db, root, |from, name| {
+        let (id, text) = driver.load_import(from, name)?;
+        Some(SourceProgram::new(&db, text, id))
+    });
+
+    times.report(driver);
+
+    let project = Project::new(&db, files, imports);
+    check(&db, driver, project, file)
+}
+
+/// Check every file in the given project, reporting any messages to the
+/// driver. The entry point is looked for in the `root` file. Since this only
+/// runs queries on the database, checking a project a second time only redoes
+/// the work affected by any changes to its inputs.
+pub fn check(db: &Database, driver: &mut impl Driver, project: Project, root: File) -> ParseResult {
+    // Every file has already been lexed and parsed when its imports were
+    // collected, so these are only looked up here.
+    let (mut tokens, mut parsed) = (0, 0);
+    for file in project.files(db) {
+        let lexed = lex::lex(db, *file);
+        let decls = parse::parse(db, lexed);
+        tokens += lexed.tokens(db).len();
+        parsed += decls.values(db).len() + decls.types(db).len();
+
+        let id = file.id(db);
+        driver.output_ir(IrOutput::Tokens(id), || dump::tokens(db, lexed));
+        driver.output_ir(IrOutput::Cst(id), || dump::cst(db, lexed));
+        driver.output_ir(IrOutput::Hir(id), || dump::unresolved(db, decls));
+    }
 
-    let tokens = lex::lex(&db, program);
-    let decls = parse::parse(&db, tokens);
-    let _decls = resolve::resolve(&db, decls);
+    driver.report_stat(Stat::Tokens, tokens);
+    driver.report_stat(Stat::Decls, parsed);
 
-    todo!()
+    let start = Instant::now();
+    let decls = resolve::resolve(db, project);
+    info!("resolved names in {:.2?}", start.elapsed());
+    driver.report_pass(Pass::Resolve, start.elapsed());
+    driver.output_ir(IrOutput::ResolvedHir, || dump::resolved(db, decls));
+    driver.output_ir(IrOutput::DepsDot, || dump::dependencies(db, decls));
 
-    // let tyckres = tyck::typeck(driver, &mut names, decls);
+    let start = Instant::now();
+    let kinds = kick::kindck(db, decls);
+    info!("kindchecked in {:.2?}", start.elapsed());
+    driver.report_pass(Pass::Kindck, start.elapsed());
+    driver.output_ir(IrOutput::Kinds, || dump::kinds(db, kinds));
 
-    // ParseResult {
-    //     checked: tyckres,
-    //     names,
-    //     entry,
-    // }
+    let start = Instant::now();
+    let defs = definitions::type_definitions(db, decls);
+    let tyckres = tyck2::typeck(db, decls);
+    info!("typechecked in {:.2?}", start.elapsed());
+    driver.report_pass(Pass::Typeck, start.elapsed());
+    driver.output_ir(IrOutput::Thir, || dump::typed(db, tyckres));
+
+    let mut messages = Messages {
+        msgs: ide::diagnostics(db, project),
+    };
+
+    let start = Instant::now();
+    let Bridged {
+        checked,
+        names,
+        name_map,
+    } = bridge::bridge(db, decls, defs, tyckres);
+    info!("bridged in {:.2?}", start.elapsed());
+    driver.report_pass(Pass::Bridge, start.elapsed());
+
+    let entry = driver.entry_name().and_then(|entry| {
+        let common = <Database as DbWithJar<zippy_common::Jar>>::as_jar_db(db);
+        let module = names2::Name::new(common, None, NamePart::File(root));
+        let entry = names2::Name::new(common, Some(module), NamePart::Source(entry));
+        let entry = name_map.get(&entry).copied();
+
+        if entry.is_none() {
+            messages.resolve_no_entry_point();
+        }
+
+        entry
+    });
+
+    let tests = decls
+        .tests(db)
+        .iter()
+        .filter_map(|test| {
+            Some(Test {
+                name: name_map.get(&test.name).copied()?,
+                description: test.description.clone(),
+                span: test.span,
+            })
+        })
+        .collect();
+
+    let exports = decls
+        .exports(db)
+        .iter()
+        .filter_map(|(name, _)| name_map.get(name).copied())
+        .collect();
+
+    driver.report(messages);
+
+    ParseResult {
+        checked,
+        names,
+        entry,
+        tests,
+        exports,
+    }
+}
+
+/// Find every file transitively imported by the `root` file. The `load`
+/// function is given the id of an importing file and the imported name, and
+/// should produce the imported file, or `None` if it can't be found. Any file
+/// is only included once, even if it is imported several times.
+pub fn collect_files(
+    db: &dyn Db,
+    root: SourceProgram,
+    mut load: impl FnMut(File, &str) -> Option<SourceProgram>,
+) -> (Vec<SourceProgram>, Imports, FileTimes) {
+    let mut files = vec![root];
+    let mut imports = Imports::new();
+    let mut times = FileTimes::default();
+    let mut worklist = vec![root];
+
+    while let Some(program) = worklist.pop() {
+        let from = program.id(db);
+
+        let start = Instant::now();
+        let tokens = lex::lex(db, program);
+        times.lex += start.elapsed();
+
+        let start = Instant::now();
+        let decls = parse::parse(db, tokens);
+        times.parse += start.elapsed();
+
+        for (name, _) in decls.imports(db).iter() {
+            let name = name.text(db);
+            let Some(imported) = load(from, name) else {
+                continue;
+            };
+
+            let id = imported.id(db);
+            imports.insert((from, name.clone()), id);
+
+            if files.iter().all(|file| file.id(db) != id) {
+                files.push(imported);
+                worklist.push(imported);
+            }
+        }
+    }
+
+    (files, imports, times)
+}
+
+/// How long was spent lexing and parsing files while collecting them. Files
+/// which haven't changed since they were last collected take no time at all.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FileTimes {
+    pub lex: Duration,
+    pub parse: Duration,
+}
+
+impl FileTimes {
+    pub fn report(self, driver: &mut impl Driver) {
+        driver.report_pass(Pass::Lex, self.lex);
+        driver.report_pass(Pass::Parse, self.parse);
+    }
 }
 
 #[salsa::accumulator]
@@ -47,12 +225,26 @@ pub struct MessageAccumulator(zippy_common::message::Diagnostic);
 pub struct SourceProgram {
     #[return_ref]
     pub text: String,
-    pub id: usize,
+    pub id: File,
+}
+
+/// Maps a file and a module name it imports to the imported file.
+pub type Imports = HashMap<(File, String), File>;
+
+/// A program made up of any number of source files.
+#[salsa::input]
+pub struct Project {
+    #[return_ref]
+    pub files: Vec<SourceProgram>,
+
+    #[return_ref]
+    pub imports: Imports,
 }
 
 #[salsa::jar(db = Db)]
 pub struct Jar(
     crate::SourceProgram,
+    crate::Project,
     crate::MessageAccumulator,
     crate::resolved::Decls,
     crate::unresolved::Name,
@@ -73,9 +265,12 @@ pub trait Db: DbWithJar<Jar> + DbWithJar<zippy_common::Jar> {}
 
 impl<DB> Db for DB where DB: DbWithJar<Jar> + DbWithJar<zippy_common::Jar> {}
 
+/// The database holding every query in the frontend. Keeping one of these
+/// around between compilations means only queries affected by a change in
+/// some [`SourceProgram`] or [`Project`] need to be recomputed.
 #[derive(Default)]
 #[salsa::db(crate::Jar, zippy_common::Jar)]
-pub(crate) struct Database {
+pub struct Database {
     storage: salsa::Storage<Self>,
 }
 
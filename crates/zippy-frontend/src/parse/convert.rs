@@ -1,18 +1,34 @@
 use zippy_common::Number;
 
-pub fn parse_dec(text: &str) -> Number {
-    let mut res = 0.into();
+/// Convert the text of a number literal, which the lexer has already checked,
+/// into a number. Decimal numbers may have a fractional part, which is kept
+/// exactly.
+pub fn parse_number(text: &str) -> Number {
+    let (digits, radix) = match text.get(..2) {
+        Some("0x") => (&text[2..], 16),
+        Some("0o") => (&text[2..], 8),
+        Some("0b") => (&text[2..], 2),
+        _ => (text, 10),
+    };
 
-    for c in text.chars() {
+    let mut res: Number = 0.into();
+    let mut scale: Number = 1.into();
+    let mut fraction = false;
+
+    for c in digits.chars() {
         match c {
-            '0'..='9' => {
-                res *= <i32 as Into<Number>>::into(10);
-                res += <u32 as Into<Number>>::into(c.to_digit(10).unwrap());
-            }
             '_' | '\'' => continue,
-            _ => unreachable!(),
+            '.' => fraction = true,
+            c => {
+                res *= <u32 as Into<Number>>::into(radix);
+                res += <u32 as Into<Number>>::into(c.to_digit(radix).unwrap());
+
+                if fraction {
+                    scale *= <u32 as Into<Number>>::into(radix);
+                }
+            }
         }
     }
 
-    res
+    res / scale
 }
@@ -61,13 +61,31 @@ where
     }
 
     /// Tokens that may start a `decl`.
-    const DECL_STARTS: &'static [Token] = &[Token::GroupOpen, Token::Fun, Token::Let, Token::Type];
+    const DECL_STARTS: &'static [Token] = &[
+        Token::GroupOpen,
+        Token::Fun,
+        Token::Let,
+        Token::Type,
+        Token::Import,
+        Token::Test,
+        Token::Export,
+        Token::Extern,
+    ];
 
     /// ```abnf
-    /// decl = type-decl / let-decl / fun-decl
+    /// decl = type-decl / let-decl / fun-decl / import-decl / test-decl
+    /// decl =/ export-decl / extern-decl
     /// ```
     fn decl(&mut self) -> Decl {
-        if let Some(span) = self.matches(Token::Type) {
+        if let Some(span) = self.matches(Token::Export) {
+            self.export_decl(span)
+        } else if let Some(span) = self.matches(Token::Extern) {
+            self.extern_decl(span)
+        } else if let Some(span) = self.matches(Token::Import) {
+            self.import_decl(span)
+        } else if let Some(span) = self.matches(Token::Test) {
+            self.test_decl(span)
+        } else if let Some(span) = self.matches(Token::Type) {
             self.type_decl(span)
         } else if let Some(span) = self.matches(Token::Let) {
             self.let_decl(span)
@@ -78,6 +96,153 @@ where
         }
     }
 
+    /// ```abnf
+    /// export-decl = "export" fun-decl
+    /// ```
+    fn export_decl(&mut self, export_span: Span) -> Decl {
+        let fun_span = match self.matches(Token::Fun) {
+            Some(span) => span,
+            None => {
+                let span = self.curr.as_ref().map(|(_, span)| *span);
+                self.msgs
+                    .at(span.unwrap_or(export_span))
+                    .parse_expected_exported_function();
+
+                // Any other declaration is parsed as usual, just without
+                // being exported, while anything else is taken to be a
+                // function missing its `fun`.
+                if self.peek(Self::DECL_STARTS) && !self.peek(Token::GroupOpen) {
+                    return self.decl();
+                }
+
+                export_span
+            }
+        };
+
+        let mut decl = self.fun_decl(fun_span);
+        if let DeclNode::FunDecl { exported, .. } = &mut decl.node {
+            *exported = true;
+        }
+
+        Decl {
+            node: decl.node,
+            span: export_span + decl.span,
+        }
+    }
+
+    /// ```abnf
+    /// extern-decl = "extern" [STRING] fun-decl
+    /// ; without implicits or a body
+    /// ```
+    fn extern_decl(&mut self, extern_span: Span) -> Decl {
+        let header = match self.matches(Token::String(String::new())) {
+            Some(_) => match &self.prev {
+                Some((Token::String(header), _)) => Some(header.clone()),
+                _ => None,
+            },
+
+            None => None,
+        };
+
+        let fun_span = match self.matches(Token::Fun) {
+            Some(span) => span,
+            None => {
+                let span = self.curr.as_ref().map(|(_, span)| *span);
+                self.msgs
+                    .at(span.unwrap_or(extern_span))
+                    .parse_expected_extern_function();
+
+                if self.peek(Self::DECL_STARTS) && !self.peek(Token::GroupOpen) {
+                    return self.decl();
+                }
+
+                extern_span
+            }
+        };
+
+        let decl = self.fun_decl(fun_span);
+        let (name, implicits, args, anno, bind) = match decl.node {
+            DeclNode::FunDecl {
+                name,
+                implicits,
+                args,
+                anno,
+                bind,
+                ..
+            } => (name, implicits, args, anno, bind),
+            _ => unreachable!(),
+        };
+
+        if let Some(implicits) = implicits {
+            self.msgs.at(implicits.span).parse_extern_implicits();
+        }
+
+        if let Some(bind) = bind {
+            self.msgs.at(bind.span).parse_extern_with_body();
+        }
+
+        Decl {
+            node: DeclNode::ExternDecl {
+                header,
+                name,
+                args,
+                anno,
+            },
+            span: extern_span + decl.span,
+        }
+    }
+
+    /// ```abnf
+    /// import-decl = "import" base-expr
+    /// ```
+    fn import_decl(&mut self, import_span: Span) -> Decl {
+        let name = self.parse_base_expr();
+        let span = name.span;
+
+        Decl {
+            node: DeclNode::ImportDecl { name },
+            span: import_span + span,
+        }
+    }
+
+    /// ```abnf
+    /// test-decl = "test" STRING ["=" expr]
+    /// ```
+    fn test_decl(&mut self, test_span: Span) -> Decl {
+        let (name, name_span) = match self.matches(Token::String(String::new())) {
+            Some(span) => match &self.prev {
+                Some((Token::String(name), _)) => (name.clone(), span),
+                _ => (String::new(), span),
+            },
+
+            None => {
+                let span = self.curr.as_ref().map(|(_, span)| *span);
+                self.msgs
+                    .at(span.unwrap_or(test_span))
+                    .parse_expected_test_name();
+                (String::new(), test_span)
+            }
+        };
+
+        let bind = self.consume(Token::Equal).then(|| self.parse_expr());
+        let span = bind.as_ref().map(|bind| bind.span).unwrap_or(name_span);
+
+        if bind.is_none() {
+            self.msgs
+                .at(test_span + name_span)
+                .parse_test_without_body();
+        }
+
+        Decl {
+            node: DeclNode::TestDecl {
+                name,
+                name_span,
+                bind,
+            },
+            span: test_span + span,
+        }
+    }
+
     /// ```abnf
     /// type-decl = "type" small-expr ["=" expr]
     /// ```
@@ -146,6 +311,7 @@ where
 
         Decl {
             node: DeclNode::FunDecl {
+                exported: false,
                 name,
                 implicits,
                 args,
@@ -1,6 +1,6 @@
 use zippy_common::message::Span;
 
-use super::convert::parse_dec;
+use super::convert::parse_number;
 use super::tree::{BinOp, Expr, ExprNode};
 use super::Parser;
 use crate::lex::Token;
@@ -110,16 +110,19 @@ where
     }
 
     /// ```abnf
-    /// range-expr = mul-expr ["upto" mul-expr]
+    /// range-expr = mul-expr ["upto" mul-expr ["by" mul-expr]]
     /// ```
     fn range_expr(&mut self) -> Expr {
         let expr = self.mul_expr();
         if let Some(op_span) = self.matches(Token::Upto) {
             let other = self.mul_expr();
-            let span = expr.span + other.span;
+            let step = self.consume(Token::By).then(|| Box::new(self.mul_expr()));
+
+            let end = step.as_ref().map_or(other.span, |step| step.span);
+            let span = expr.span + end;
 
             Expr {
-                node: ExprNode::Range(op_span, Box::new(expr), Box::new(other)),
+                node: ExprNode::Range(op_span, Box::new(expr), Box::new(other), step),
                 span,
             }
         } else {
@@ -193,13 +196,19 @@ where
     pub const BASE_EXPR_STARTS: &'static [Token] = &[
         Token::Name(String::new()),
         Token::Number(String::new()),
+        Token::String(String::new()),
+        Token::Char('\0'),
         Token::Question,
         Token::GroupOpen,
         Token::Type,
+        Token::Text,
+        Token::Minus,
     ];
 
     /// ```abnf
-    /// base-expr  = NAME / NUM / WILDCARD
+    /// base-expr  = NAME / NUM / STRING / CHAR / WILDCARD
+    /// base-expr =/ "type" / "text"
+    /// base-expr =/ "-" base-expr
     /// base-expr =/ "(" expr ")"
     /// base-expr =/ "(" OP-NAME ")"
     /// ```
@@ -208,9 +217,31 @@ where
         if let Some((tok, span)) = self.prev.take() {
             let node = match tok {
                 Token::Name(name) => ExprNode::Name(name),
-                Token::Number(num) => ExprNode::Num(parse_dec(&num)),
+                Token::Number(num) => ExprNode::Num(parse_number(&num)),
+                Token::String(text) => ExprNode::Str(text),
+
+                // A character is just the number of its code point.
+                Token::Char(c) => ExprNode::Num(u32::from(c).into()),
                 Token::Question => ExprNode::Wildcard,
                 Token::Type => ExprNode::Type,
+                Token::Text => ExprNode::Text,
+                Token::Minus => {
+                    if !self.peek(Self::BASE_EXPR_STARTS) {
+                        self.msgs.at(span).parse_expected_base_expr();
+                        return Expr {
+                            node: ExprNode::Invalid,
+                            span,
+                        };
+                    }
+
+                    let expr = self.parse_base_expr();
+                    let span = span + expr.span;
+                    return Expr {
+                        node: ExprNode::Neg(Box::new(expr)),
+                        span,
+                    };
+                }
+
                 Token::GroupOpen => {
                     let expr = if self.peek(Self::OP_NAME_STARTS) {
                         self.op_name()
@@ -10,6 +10,8 @@ impl Matcher for Token {
             (_, Token::Invalid) => true,
             (Token::Name(..), Token::Name(..)) => true,
             (Token::Number(..), Token::Number(..)) => true,
+            (Token::String(..), Token::String(..)) => true,
+            (Token::Char(..), Token::Char(..)) => true,
             (t, u) => t == u,
         }
     }
@@ -27,7 +27,7 @@ pub fn parse(db: &dyn crate::Db, tokens: Tokens) -> unresolved::Decls {
         MessageAccumulator::push(db, msg);
     }
 
-    let mut unconcer = Unconcretifier::new(db);
+    let mut unconcer = Unconcretifier::new(db, tokens.docs(db));
     let decls = unconcer.unconcretify(decls);
 
     for msg in unconcer.msgs.msgs {
@@ -39,6 +39,15 @@ pub fn parse(db: &dyn crate::Db, tokens: Tokens) -> unresolved::Decls {
     decls
 }
 
+/// Parse some tokens into a concrete syntax tree outside of the database. This
+/// is used by tools like the formatter, which care about how the code was
+/// written rather than what it means.
+pub fn parse_concrete(tokens: Vec<(Token, Span)>, file: File) -> (Vec<tree::Decl>, Messages) {
+    let mut parser = Parser::new(tokens, file);
+    let decls = parser.parse_program();
+    (decls, parser.msgs)
+}
+
 #[derive(Debug)]
 struct Parser<I> {
     tokens: I,
@@ -17,6 +17,9 @@ pub enum DeclNode {
     },
 
     FunDecl {
+        /// Whether the function was declared with `export fun`, which makes
+        /// it callable from C code linking against the compiled program.
+        exported: bool,
         name: Expr,
         implicits: Option<Expr>,
         args: Vec<Expr>,
@@ -24,10 +27,29 @@ pub enum DeclNode {
         bind: Option<Expr>,
     },
 
+    /// A function implemented in C, declared with `extern fun`, optionally
+    /// along with the header declaring it.
+    ExternDecl {
+        header: Option<String>,
+        name: Expr,
+        args: Vec<Expr>,
+        anno: Option<Expr>,
+    },
+
     TypeDecl {
         pat: Expr,
         bind: Option<Expr>,
     },
+
+    ImportDecl {
+        name: Expr,
+    },
+
+    TestDecl {
+        name: String,
+        name_span: Span,
+        bind: Option<Expr>,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -40,13 +62,16 @@ pub struct Expr {
 pub enum ExprNode {
     Name(Name),
     Num(Number),
+    Str(String),
 
     Group(Box<Expr>),
 
-    Range(Span, Box<Expr>, Box<Expr>),
+    /// A range with an optional resolution, like `0 upto 1 by 0.1`.
+    Range(Span, Box<Expr>, Box<Expr>, Option<Box<Expr>>),
     Fun(Span, Box<Expr>, Box<Expr>),
 
     BinOp(Span, BinOp, Box<Expr>, Box<Expr>),
+    Neg(Box<Expr>),
 
     Tuple(Box<Expr>, Box<Expr>),
 
@@ -58,6 +83,7 @@ pub enum ExprNode {
 
     Wildcard,
     Type,
+    Text,
 
     Invalid,
 }
@@ -2,7 +2,7 @@
 //! `(x => x) => x` gives a valid parse. The job of this module is to produce a HIR tree, validating away cases like
 //! that in the process.
 
-use zippy_common::hir::BindIdGenerator;
+use zippy_common::hir::{BindIdGenerator, Extern};
 use zippy_common::message::{Messages, Span};
 
 use super::tree as cst;
@@ -12,14 +12,18 @@ pub struct Unconcretifier<'a> {
     pub msgs: Messages,
     bind_id: BindIdGenerator,
     db: &'a dyn crate::Db,
+
+    /// Documentation comments by the offset of the token following them.
+    docs: &'a [(usize, String)],
 }
 
 impl<'a> Unconcretifier<'a> {
-    pub fn new(db: &'a dyn crate::Db) -> Self {
+    pub fn new(db: &'a dyn crate::Db, docs: &'a [(usize, String)]) -> Self {
         Self {
             msgs: Messages::new(),
             bind_id: BindIdGenerator::new(),
             db,
+            docs,
         }
     }
 
@@ -30,9 +34,68 @@ impl<'a> Unconcretifier<'a> {
     fn unconc_decls(&mut self, decls: Vec<cst::Decl>) -> hir::Decls {
         let mut values = Vec::with_capacity(decls.len());
         let mut types = Vec::new();
+        let mut imports = Vec::new();
+        let mut tests = Vec::new();
+        let mut exports = Vec::new();
 
         for decl in decls {
+            let doc = self.doc(decl.span);
+
             match decl.node {
+                cst::DeclNode::ImportDecl { name } => match name.node {
+                    cst::ExprNode::Name(text) => {
+                        imports.push((hir::Name::new(self.db, text), name.span));
+                    }
+
+                    _ => self.msgs.at(name.span).parse_not_a_module_name(),
+                },
+
+                // Tests are turned into ordinary values with a name that can't
+                // be written in source code, so that they are checked and
+                // evaluated like any other value.
+                cst::DeclNode::TestDecl {
+                    name: description,
+                    name_span,
+                    bind,
+                } => {
+                    let name = hir::Name::new(self.db, format!("_test{}", tests.len()));
+                    let pat = hir::Pat {
+                        node: hir::PatNode::Name(name),
+                        span: name_span,
+                    };
+
+                    let anno = hir::Type {
+                        node: hir::TypeNode::Wildcard,
+                        span: name_span,
+                    };
+
+                    // The parser has already reported the missing body.
+                    let bind = if let Some(bind) = bind {
+                        self.unconc_expr(bind)
+                    } else {
+                        hir::Expr {
+                            node: hir::ExprNode::Invalid,
+                            span: decl.span,
+                        }
+                    };
+
+                    values.push(hir::ValueDef {
+                        span: decl.span,
+                        id: self.bind_id.fresh(),
+                        implicits: Vec::new(),
+                        pat,
+                        anno,
+                        bind,
+                        doc,
+                    });
+
+                    tests.push(hir::TestDef {
+                        span: decl.span,
+                        name,
+                        description,
+                    });
+                }
+
                 cst::DeclNode::TypeDecl { pat, bind } => {
                     let (pat, insts) = self.unconc_pat(pat);
 
@@ -60,6 +123,7 @@ impl<'a> Unconcretifier<'a> {
                         anno,
                         bind,
                         pat,
+                        doc,
                     });
                 }
 
@@ -88,10 +152,84 @@ impl<'a> Unconcretifier<'a> {
                         pat,
                         anno,
                         bind,
+                        doc,
+                    });
+                }
+
+                // External functions are turned into ordinary functions whose
+                // body calls the function in C.
+                cst::DeclNode::ExternDecl {
+                    header,
+                    name,
+                    args,
+                    anno,
+                } => {
+                    let (pat, insts) = self.unconc_pat(name);
+
+                    if !insts.is_empty() {
+                        let span = insts.into_iter().map(|ex| ex.span).sum();
+                        self.msgs.at(span).parse_extern_implicits();
+                    }
+
+                    let anno =
+                        anno.map(|anno| self.unconc_type(anno))
+                            .unwrap_or_else(|| hir::Type {
+                                node: hir::TypeNode::Wildcard,
+                                span: pat.span,
+                            });
+
+                    let node = match &pat.node {
+                        hir::PatNode::Name(name) => hir::ExprNode::Extern(Extern {
+                            symbol: name.text(self.db).clone(),
+                            header,
+                        }),
+
+                        _ => hir::ExprNode::Invalid,
+                    };
+
+                    let body = hir::Expr {
+                        node,
+                        span: pat.span,
+                    };
+
+                    let span = body.span + anno.span;
+                    let mut bind = hir::Expr {
+                        node: hir::ExprNode::Anno(Box::new(body), anno),
+                        span,
+                    };
+
+                    for arg in args.into_iter().rev() {
+                        let (arg, implicits) = self.unconc_pat(arg);
+
+                        if !implicits.is_empty() {
+                            let span = implicits.into_iter().map(|ex| ex.span).sum();
+                            self.msgs.at(span).parse_disallowed_implicits();
+                        }
+
+                        let span = bind.span + arg.span;
+                        bind = hir::Expr {
+                            node: hir::ExprNode::Lam(self.bind_id.fresh(), arg, Box::new(bind)),
+                            span,
+                        };
+                    }
+
+                    let span = pat.span;
+                    values.push(hir::ValueDef {
+                        span: decl.span,
+                        id: self.bind_id.fresh(),
+                        implicits: Vec::new(),
+                        pat,
+                        anno: hir::Type {
+                            node: hir::TypeNode::Wildcard,
+                            span,
+                        },
+                        bind,
+                        doc,
                     });
                 }
 
                 cst::DeclNode::FunDecl {
+                    exported,
                     name,
                     implicits,
                     args,
@@ -100,6 +238,10 @@ impl<'a> Unconcretifier<'a> {
                 } => {
                     let (pat, insts) = self.unconc_pat(name);
 
+                    if let (true, hir::PatNode::Name(name)) = (exported, &pat.node) {
+                        exports.push((*name, pat.span));
+                    }
+
                     let mut implicits_error = false;
                     if !insts.is_empty() {
                         let span = insts.into_iter().map(|ex| ex.span).sum();
@@ -159,6 +301,7 @@ impl<'a> Unconcretifier<'a> {
                             span,
                         },
                         bind,
+                        doc,
                     });
                 }
             }
@@ -166,15 +309,32 @@ impl<'a> Unconcretifier<'a> {
 
         values.shrink_to_fit();
 
-        hir::Decls::new(self.db, values, types)
+        hir::Decls::new(self.db, values, types, imports, tests, exports)
+    }
+
+    /// Get the documentation comment right before the declaration with the
+    /// given span.
+    fn doc(&self, span: Span) -> Option<String> {
+        self.docs
+            .iter()
+            .find(|(at, _)| *at == span.start)
+            .map(|(_, doc)| doc.clone())
     }
 
     fn unconc_expr(&mut self, expr: cst::Expr) -> hir::Expr {
         let node = match expr.node {
             cst::ExprNode::Name(name) => hir::ExprNode::Name(self.unconc_name(name)),
             cst::ExprNode::Num(i) => hir::ExprNode::Num(i),
+            cst::ExprNode::Str(text) => hir::ExprNode::Str(text),
             cst::ExprNode::Group(expr) => return self.unconc_expr(*expr),
-            cst::ExprNode::Range(span, lo, hi) => {
+            cst::ExprNode::Neg(inner) => match self.unconc_expr(*inner) {
+                hir::Expr {
+                    node: hir::ExprNode::Num(i),
+                    ..
+                } => hir::ExprNode::Num(-i),
+                inner => hir::ExprNode::Neg(Box::new(inner)),
+            },
+            cst::ExprNode::Range(span, lo, hi, step) => {
                 let lo = Box::new(self.unconc_expr(*lo));
                 let hi = Box::new(self.unconc_expr(*hi));
                 let fun = hir::Expr {
@@ -188,7 +348,32 @@ impl<'a> Unconcretifier<'a> {
                     span,
                 };
 
-                hir::ExprNode::App(Box::new(fun), hi)
+                let range = hir::ExprNode::App(Box::new(fun), hi);
+                let Some(step) = step else {
+                    return hir::Expr {
+                        node: range,
+                        span: expr.span,
+                    };
+                };
+
+                // `a upto b by c` is `by (upto a b) c`
+                let step = Box::new(self.unconc_expr(*step));
+                let range = hir::Expr {
+                    node: range,
+                    span: expr.span,
+                };
+
+                let fun = hir::Expr {
+                    node: hir::ExprNode::Name(self.unconc_name("by".into())),
+                    span: step.span,
+                };
+
+                let fun = hir::Expr {
+                    node: hir::ExprNode::App(Box::new(fun), Box::new(range)),
+                    span: expr.span,
+                };
+
+                hir::ExprNode::App(Box::new(fun), step)
             }
             cst::ExprNode::Fun(span, t, u) => {
                 let t = Box::new(self.unconc_expr(*t));
@@ -261,7 +446,7 @@ impl<'a> Unconcretifier<'a> {
             cst::ExprNode::Wildcard => hir::ExprNode::Hole,
             cst::ExprNode::Invalid => hir::ExprNode::Invalid,
 
-            cst::ExprNode::Type => {
+            cst::ExprNode::Type | cst::ExprNode::Text => {
                 self.msgs.at(expr.span).parse_expected_expr();
                 hir::ExprNode::Invalid
             }
@@ -328,11 +513,12 @@ impl<'a> Unconcretifier<'a> {
 
     fn unconc_type(&mut self, ty: cst::Expr) -> hir::Type {
         let node = match ty.node {
-            cst::ExprNode::Range(_, lo, hi) => {
+            cst::ExprNode::Range(_, lo, hi, step) => {
                 let lo = self.unconc_expr(*lo);
                 let hi = self.unconc_expr(*hi);
+                let step = step.map(|step| Box::new(self.unconc_expr(*step)));
 
-                hir::TypeNode::Range(Box::new(lo), Box::new(hi))
+                hir::TypeNode::Range(Box::new(lo), Box::new(hi), step)
             }
 
             cst::ExprNode::Name(name) => hir::TypeNode::Name(self.unconc_name(name)),
@@ -348,7 +534,7 @@ impl<'a> Unconcretifier<'a> {
                     span: ty.span,
                 };
 
-                hir::TypeNode::Range(Box::new(lo), Box::new(hi))
+                hir::TypeNode::Range(Box::new(lo), Box::new(hi), None)
             }
 
             cst::ExprNode::Fun(_, t, u) => {
@@ -367,6 +553,8 @@ impl<'a> Unconcretifier<'a> {
 
             cst::ExprNode::Type => hir::TypeNode::Type,
 
+            cst::ExprNode::Text => hir::TypeNode::Text,
+
             cst::ExprNode::Wildcard => hir::TypeNode::Wildcard,
 
             _ => {
@@ -5,7 +5,13 @@ use crate::unresolved::{Expr, ExprNode};
 impl Resolver<'_> {
     pub fn declare_expr(&mut self, expr: &Expr) {
         match &expr.node {
-            ExprNode::Name(_) | ExprNode::Num(_) | ExprNode::Hole | ExprNode::Invalid => {}
+            ExprNode::Name(_)
+            | ExprNode::Num(_)
+            | ExprNode::Str(_)
+            | ExprNode::Extern(_)
+            | ExprNode::Hole
+            | ExprNode::Invalid => {}
+
             ExprNode::Lam(id, param, body) => {
                 self.in_scope_mut(expr.span, NamePart::Scope(*id), |this| {
                     this.declare_pat(param);
@@ -27,7 +33,7 @@ impl Resolver<'_> {
                 self.declare_expr(y);
             }
 
-            ExprNode::Anno(expr, _) => self.declare_expr(expr),
+            ExprNode::Neg(expr) | ExprNode::Anno(expr, _) => self.declare_expr(expr),
         }
     }
 }
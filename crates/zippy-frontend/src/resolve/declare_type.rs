@@ -11,12 +11,16 @@ impl Resolver<'_> {
                 self.declare_type(u);
             }
 
-            TypeNode::Range(lo, hi) => {
+            TypeNode::Range(lo, hi, step) => {
                 self.declare_expr(lo);
                 self.declare_expr(hi);
+
+                if let Some(step) = step {
+                    self.declare_expr(step);
+                }
             }
 
-            TypeNode::Type | TypeNode::Wildcard | TypeNode::Invalid => {}
+            TypeNode::Type | TypeNode::Text | TypeNode::Wildcard | TypeNode::Invalid => {}
         }
     }
 }
@@ -12,28 +12,50 @@ use std::collections::HashMap;
 
 use log::{debug, info};
 use zippy_common::{
-    message::{Messages, Span},
+    message::{File, Messages, Span},
     names2::{self, Name},
 };
 
 use self::path::{NamePart, Path};
-use crate::{resolved, unresolved, Db, MessageAccumulator};
+use crate::{lex, parse, resolved, unresolved, Db, MessageAccumulator, Project};
 
 #[salsa::tracked]
-pub fn resolve(db: &dyn Db, decls: unresolved::Decls) -> resolved::Decls {
+pub fn resolve(db: &dyn Db, project: Project) -> resolved::Decls {
     info!("beginning name resolution");
     debug!("declaring names");
 
+    let files: Vec<_> = project
+        .files(db)
+        .iter()
+        .map(|file| (file.id(db), parse::parse(db, lex::lex(db, *file))))
+        .collect();
+
     let mut resolver = Resolver::new(db);
-    resolver.declare_decls(&decls);
+
+    for (file, decls) in files.iter() {
+        let span = Span::new(*file, 0, 0);
+        resolver.in_scope_mut(span, NamePart::File(*file), |this| {
+            this.declare_decls(decls);
+        });
+    }
 
     debug!("resolving names");
 
-    let decls = resolver.resolve_decls(decls);
+    let mut values = Vec::new();
+    let mut types = Vec::new();
+    let mut tests = Vec::new();
+    let mut exports = Vec::new();
+
+    for (file, decls) in files {
+        resolver.resolve_imports(project, file, decls);
+        resolver.in_scope(NamePart::File(file), |this| {
+            this.resolve_decls(&mut values, &mut types, &mut tests, &mut exports, decls);
+        });
+    }
 
     debug!("name resolution done");
 
-    decls
+    resolved::Decls::new(db, values, types, tests, exports)
 }
 
 pub struct Resolver<'a> {
@@ -45,6 +67,10 @@ pub struct Resolver<'a> {
     /// name of the innermost containing name (if any).
     context: (Vec<NamePart>, Option<Name>),
 
+    /// The files imported by the file currently being resolved, along with the
+    /// names they were imported by.
+    imports: Vec<(String, File)>,
+
     db: &'a dyn Db,
 }
 
@@ -54,6 +80,7 @@ impl<'a> Resolver<'a> {
             names: HashMap::new(),
             //generator: NameGenerator::new(),
             context: (Vec::new(), None),
+            imports: Vec::new(),
 
             db,
         }
@@ -63,6 +90,7 @@ impl<'a> Resolver<'a> {
     /// the name has already been declared.
     fn declare(&mut self, _span: Span, part: NamePart) -> Name {
         let name = match &part {
+            NamePart::File(file) => names2::NamePart::File(*file),
             NamePart::Scope(id) => names2::NamePart::Scope(*id),
             NamePart::Source(name) => {
                 let text = name.text(self.db);
@@ -125,8 +153,9 @@ impl<'a> Resolver<'a> {
         res
     }
 
-    /// Lookup a given unqualified name in the current context. Returns `None`
-    /// and emits an error message if the name could not be found.
+    /// Lookup a given unqualified name in the current context, and then in the
+    /// imported files. Returns `None` and emits an error message if the name
+    /// could not be found, or if several imported files declare it.
     fn lookup(&self, span: Span, name: unresolved::Name) -> Option<Name> {
         let mut path = Path(self.context.0.clone(), NamePart::Source(name));
 
@@ -138,14 +167,63 @@ impl<'a> Resolver<'a> {
             path.0.pop();
         }
 
-        let result = self.names.get(&path).copied();
+        if let Some(name) = self.names.get(&path) {
+            return Some(*name);
+        }
 
-        if result.is_none() {
-            let name = name.text(self.db);
-            self.report_unresolved(span, name);
+        // The same file may be imported more than once, so only distinct names
+        // make a name ambiguous.
+        let mut found: Vec<(&str, Name)> = Vec::new();
+        for (module, file) in self.imports.iter() {
+            let path = Path(vec![NamePart::File(*file)], NamePart::Source(name));
+            if let Some(&imported) = self.names.get(&path) {
+                if found.iter().all(|(_, other)| *other != imported) {
+                    found.push((module.as_str(), imported));
+                }
+            }
         }
 
-        result
+        match found[..] {
+            [(_, imported)] => Some(imported),
+            [] => {
+                self.report_unresolved(span, name.text(self.db));
+                None
+            }
+
+            [(first, _), (second, _), ..] => {
+                let mut messages = Messages::new();
+                messages
+                    .at(span)
+                    .resolve_ambiguous_name(name.text(self.db), first, second);
+
+                for message in messages.msgs {
+                    MessageAccumulator::push(self.db, message);
+                }
+
+                None
+            }
+        }
+    }
+
+    /// Figure out which files are imported by the given file, reporting an
+    /// error for any import which doesn't refer to a known file.
+    fn resolve_imports(&mut self, project: Project, file: File, decls: unresolved::Decls) {
+        self.imports.clear();
+
+        for (name, span) in decls.imports(self.db).iter() {
+            let name = name.text(self.db);
+            match project.imports(self.db).get(&(file, name.clone())) {
+                Some(imported) => self.imports.push((name.clone(), *imported)),
+                None => {
+                    let mut messages = Messages::new();
+                    messages.at(*span).resolve_unknown_module(name);
+
+                    for message in messages.msgs {
+                        MessageAccumulator::push(self.db, message);
+                    }
+                }
+            }
+        }
     }
 
     fn report_unresolved(&self, span: Span, name: &str) {
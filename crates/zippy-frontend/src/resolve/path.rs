@@ -1,9 +1,11 @@
 use zippy_common::hir::BindId;
+use zippy_common::message::File;
 
 use crate::unresolved::Name;
 
 #[derive(Clone, Copy, Eq, Hash, PartialEq)]
 pub enum NamePart {
+    File(File),
     Source(Name),
     Scope(BindId),
 }
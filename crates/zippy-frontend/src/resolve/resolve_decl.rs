@@ -1,26 +1,42 @@
+use zippy_common::message::Span;
+use zippy_common::names2::Name;
+
 use super::path::NamePart;
 use super::Resolver;
-use crate::resolved::{Decls, TypeDef, ValueDef};
+use crate::resolved::{TestDef, TypeDef, ValueDef};
 use crate::unresolved;
 
 impl Resolver<'_> {
-    pub fn resolve_decls(&mut self, decls: unresolved::Decls) -> Decls {
-        let unresolved_values = decls.values(self.db);
-        let unresolved_types = decls.types(self.db);
-
-        let mut values = Vec::with_capacity(unresolved_values.len());
-        let mut types = Vec::with_capacity(unresolved_values.len());
-
-        for def in unresolved_values.iter().cloned() {
-            let value = self.resolve_value_def(&mut values, def);
+    pub fn resolve_decls(
+        &mut self,
+        values: &mut Vec<ValueDef>,
+        types: &mut Vec<TypeDef>,
+        tests: &mut Vec<TestDef>,
+        exports: &mut Vec<(Name, Span)>,
+        decls: unresolved::Decls,
+    ) {
+        for def in decls.values(self.db).iter().cloned() {
+            let value = self.resolve_value_def(values, def);
             values.push(value);
         }
 
-        for def in unresolved_types.iter().cloned() {
-            types.push(self.resolve_type_def(&mut values, def));
+        for def in decls.types(self.db).iter().cloned() {
+            types.push(self.resolve_type_def(values, def));
         }
 
-        Decls::new(self.db, values, types)
+        for def in decls.tests(self.db).iter().cloned() {
+            // The name of a test is declared like any other value.
+            let name = self.lookup(def.span, def.name).unwrap();
+            tests.push(TestDef {
+                span: def.span,
+                name,
+                description: def.description,
+            });
+        }
+
+        for (name, span) in decls.exports(self.db).iter() {
+            exports.extend(self.lookup(*span, *name).map(|name| (name, *span)));
+        }
     }
 
     fn resolve_value_def(
@@ -46,6 +62,7 @@ impl Resolver<'_> {
                 implicits,
                 anno,
                 bind,
+                doc: def.doc,
             }
         })
     }
@@ -66,6 +83,7 @@ impl Resolver<'_> {
                 pat,
                 anno,
                 bind,
+                doc: def.doc,
             }
         })
     }
@@ -7,6 +7,7 @@ impl Resolver<'_> {
     pub fn resolve_expr(&mut self, values: &mut Vec<ValueDef>, expr: unresolved::Expr) -> Expr {
         let node = match expr.node {
             unresolved::ExprNode::Num(v) => ExprNode::Num(v),
+            unresolved::ExprNode::Str(text) => ExprNode::Str(text),
 
             unresolved::ExprNode::Name(name) => match self.lookup(expr.span, name) {
                 Some(name) => ExprNode::Name(name),
@@ -48,6 +49,8 @@ impl Resolver<'_> {
                 ExprNode::Tuple(x, y)
             }
 
+            unresolved::ExprNode::Neg(x) => ExprNode::Neg(Box::new(self.resolve_expr(values, *x))),
+
             unresolved::ExprNode::Anno(x, ty) => {
                 let x = Box::new(self.resolve_expr(values, *x));
                 let ty = self.resolve_type(values, ty);
@@ -55,6 +58,7 @@ impl Resolver<'_> {
                 ExprNode::Anno(x, ty)
             }
 
+            unresolved::ExprNode::Extern(external) => ExprNode::Extern(external),
             unresolved::ExprNode::Hole => ExprNode::Hole,
             unresolved::ExprNode::Invalid => ExprNode::Invalid,
         };
@@ -26,17 +26,23 @@ impl Resolver<'_> {
                 TypeNode::Fun(t, u)
             }
 
-            unresolved::TypeNode::Range(lo, hi) => {
+            unresolved::TypeNode::Range(lo, hi, step) => {
                 let lo = self.resolve_expr(values, *lo);
                 let hi = self.resolve_expr(values, *hi);
 
                 let lo = self.lift_expr(values, lo);
                 let hi = self.lift_expr(values, hi);
 
-                TypeNode::Range(lo, hi)
+                let step = step.map(|step| {
+                    let step = self.resolve_expr(values, *step);
+                    self.lift_expr(values, step)
+                });
+
+                TypeNode::Range(lo, hi, step)
             }
 
             unresolved::TypeNode::Type => TypeNode::Type,
+            unresolved::TypeNode::Text => TypeNode::Text,
             unresolved::TypeNode::Wildcard => TypeNode::Wildcard,
             unresolved::TypeNode::Invalid => TypeNode::Invalid,
         };
@@ -47,8 +53,8 @@ impl Resolver<'_> {
         }
     }
 
-    /// Lift an expression from a range bound into a top-level binding, and
-    /// return its name.
+    /// Lift an expression from a range bound or resolution into a top-level
+    /// binding, and return its name.
     fn lift_expr(&mut self, into: &mut Vec<ValueDef>, ex: Expr) -> Name {
         let span = ex.span;
         let name = Name::new(self.common_db(), self.context.1, NamePart::Spanned(span));
@@ -65,6 +71,7 @@ impl Resolver<'_> {
             },
             bind: ex,
             span,
+            doc: None,
         });
 
         name
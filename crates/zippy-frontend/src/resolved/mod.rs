@@ -3,6 +3,7 @@
 //! This essentially means that all names live in a global scope, which
 //! simplifies many later passes.
 
+use zippy_common::hir::Extern;
 use zippy_common::message::Span;
 use zippy_common::names2::Name;
 use zippy_common::Number;
@@ -15,6 +16,21 @@ pub struct Decls {
 
     #[return_ref]
     pub types: Vec<TypeDef>,
+
+    #[return_ref]
+    pub tests: Vec<TestDef>,
+
+    /// The name of every function declared with `export fun`.
+    #[return_ref]
+    pub exports: Vec<(Name, Span)>,
+}
+
+/// A test, whose body is given by the value definition with the same name.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TestDef {
+    pub span: Span,
+    pub name: Name,
+    pub description: String,
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -23,6 +39,7 @@ pub struct TypeDef {
     pub pat: Pat,
     pub anno: Type,
     pub bind: Type,
+    pub doc: Option<String>,
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -32,6 +49,7 @@ pub struct ValueDef {
     pub implicits: Vec<Name>,
     pub anno: Type,
     pub bind: Expr,
+    pub doc: Option<String>,
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -44,15 +62,18 @@ pub struct Expr {
 pub enum ExprNode {
     Name(Name),
     Num(Number),
+    Str(String),
 
     Lam(Pat, Box<Expr>),
     App(Box<Expr>, Box<Expr>),
     Inst(Box<Expr>, Vec<Type>),
 
     Tuple(Box<Expr>, Box<Expr>),
+    Neg(Box<Expr>),
 
     Anno(Box<Expr>, Type),
 
+    Extern(Extern),
     Hole,
     Invalid,
 }
@@ -81,11 +102,14 @@ pub struct Type {
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum TypeNode {
     Name(Name),
-    Range(Name, Name),
+    /// A range from one bound to another, with an optional resolution. These
+    /// are the names of the definitions the expressions were lifted into.
+    Range(Name, Name, Option<Name>),
     Fun(Box<Type>, Box<Type>),
     Product(Box<Type>, Box<Type>),
     Type,
     Number,
+    Text,
     Wildcard,
     Invalid,
 }
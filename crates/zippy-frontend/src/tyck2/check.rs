@@ -30,6 +30,18 @@ impl Typer<'_> {
                 (hir2::ExprNode::Tuple(x, y), ty)
             }
 
+            resolved::ExprNode::Neg(x) => {
+                // The operand keeps its own type, which usually can't hold its
+                // negation, and is widened when it is negated.
+                let ty = self.type_number(because, expr.span, against);
+                let x = Box::new(self.infer(x));
+                self.type_number(Because::Negated(expr.span), x.span, x.data.clone());
+                (hir2::ExprNode::Neg(x), ty)
+            }
+
+            resolved::ExprNode::Extern(external) => {
+                (hir2::ExprNode::Extern(external.clone()), against)
+            }
             resolved::ExprNode::Hole => (hir2::ExprNode::Hole, against),
 
             _ => {
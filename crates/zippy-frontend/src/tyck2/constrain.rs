@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use zippy_common::hir2::{Because, Coercion, CoercionId, Constraint, Mutability, Type, UniVar};
+use zippy_common::hir2::{
+    merge_insts, Because, Coercion, CoercionId, Constraint, Mutability, Type, UniVar,
+};
 use zippy_common::message::Span;
 use zippy_common::names2::Name;
 
@@ -114,14 +116,18 @@ impl Typer<'_> {
     }
 
     pub fn type_function(&mut self, because: Because, span: Span, ty: Type) -> (Type, Type) {
+        let (inst, ty) = self.shallow_resolve(ty);
+
         match ty {
-            Type::Fun(t, u) => (*t, *u),
+            Type::Fun(t, u) => (instantiated(inst.clone(), *t), instantiated(inst, *u)),
+            Type::Invalid => (Type::Invalid, Type::Invalid),
+
             ty @ Type::Var(..) => {
                 let t = Type::Var(Mutability::Mutable, self.context.fresh());
                 let u = Type::Var(Mutability::Mutable, self.context.fresh());
                 self.equate(
                     span,
-                    ty,
+                    instantiated(inst, ty),
                     Type::Fun(Box::new(t.clone()), Box::new(u.clone())),
                 );
 
@@ -137,12 +143,15 @@ impl Typer<'_> {
     }
 
     pub fn type_number(&mut self, because: Because, span: Span, ty: Type) -> Type {
-        match ty {
-            ty @ Type::Range(..) => ty,
-            Type::Number => Type::Number,
+        let (_, resolved) = self.shallow_resolve(ty.clone());
+
+        match resolved {
+            Type::Range(..) | Type::Number => ty,
             Type::Invalid => Type::Invalid,
 
-            ty @ Type::Var(..) => {
+            Type::Name(name) if self.is_numeric(&name) => ty,
+
+            Type::Var(..) => {
                 self.constraints.push(Constraint::NumberType {
                     at: span,
                     because,
@@ -161,15 +170,17 @@ impl Typer<'_> {
     }
 
     pub fn type_tuple(&mut self, span: Span, ty: Type) -> (Type, Type) {
+        let (inst, ty) = self.shallow_resolve(ty);
+
         match ty {
-            Type::Product(t, u) => (*t, *u),
+            Type::Product(t, u) => (instantiated(inst.clone(), *t), instantiated(inst, *u)),
             Type::Invalid => (Type::Invalid, Type::Invalid),
 
             ty @ Type::Var(..) => {
                 let a = Type::Var(Mutability::Mutable, self.context.fresh());
                 let b = Type::Var(Mutability::Mutable, self.context.fresh());
                 let other = Type::Product(Box::new(a.clone()), Box::new(b.clone()));
-                self.equate(span, ty, other);
+                self.equate(span, instantiated(inst, ty), other);
                 (a, b)
             }
 
@@ -180,4 +191,42 @@ impl Typer<'_> {
             }
         }
     }
+
+    /// Follow any solved unification variables and instantiations at the top of
+    /// the given type. Returns the first type which is neither, together with
+    /// the instantiations collected along the way.
+    fn shallow_resolve(&self, mut ty: Type) -> (HashMap<Name, Type>, Type) {
+        let mut inst = HashMap::new();
+
+        loop {
+            ty = match ty {
+                Type::Var(mutability, var) => match self.subst.get(&var) {
+                    Some((other, solved)) => {
+                        inst = merge_insts(&inst, other);
+                        solved.make_mutability(mutability)
+                    }
+
+                    None => return (inst, Type::Var(mutability, var)),
+                },
+
+                Type::Instantiated(inner, other) => {
+                    inst = merge_insts(&inst, &other);
+                    *inner
+                }
+
+                Type::Name(name) if inst.contains_key(&name) => inst.get(&name).unwrap().clone(),
+
+                ty => return (inst, ty),
+            };
+        }
+    }
+}
+
+/// Wrap the type in the given instantiation, unless the instantiation is empty.
+fn instantiated(inst: HashMap<Name, Type>, ty: Type) -> Type {
+    if inst.is_empty() {
+        ty
+    } else {
+        Type::Instantiated(Box::new(ty), inst)
+    }
 }
@@ -69,10 +69,16 @@ impl Typer<'_> {
                 return self.check(Because::Annotation(span), expr, ty);
             }
 
+            resolved::ExprNode::Str(text) => (hir2::ExprNode::Str(text.clone()), Type::Text),
+
             resolved::ExprNode::Invalid => (hir2::ExprNode::Invalid, Type::Invalid),
 
-            resolved::ExprNode::Hole
+            // Like a number, a negation gets its type from where it is used,
+            // since the type of its operand can't hold it in general.
+            resolved::ExprNode::Extern(_)
+            | resolved::ExprNode::Hole
             | resolved::ExprNode::Num(_)
+            | resolved::ExprNode::Neg(_)
             | resolved::ExprNode::Lam(..)
             | resolved::ExprNode::Tuple(..) => {
                 self.messages.at(expr.span).tyck_ambiguous();
@@ -11,7 +11,7 @@ where
 {
     match &ty.node {
         resolved::TypeNode::Name(name) => Type::Name(*name),
-        resolved::TypeNode::Range(lo, hi) => Type::Range(*lo, *hi),
+        resolved::TypeNode::Range(lo, hi, step) => Type::Range(*lo, *hi, *step),
 
         resolved::TypeNode::Fun(t, u) => {
             let t = Box::new(lower_type(w, t));
@@ -27,6 +27,7 @@ where
 
         resolved::TypeNode::Type => Type::Type,
         resolved::TypeNode::Number => Type::Number,
+        resolved::TypeNode::Text => Type::Text,
         resolved::TypeNode::Wildcard => w(),
         resolved::TypeNode::Invalid => Type::Invalid,
     }
@@ -109,13 +109,18 @@ impl<'a> Typer<'a> {
             for value in these_values {
                 let anno = self.lower_type(&value.anno, Mutability::Mutable);
                 let pat = self.bind_pat_schema(&value.pat, anno, &value.implicits);
-                bound_values.push((pat, &value.bind, value.span));
+                bound_values.push((pat, value));
             }
 
             // check values
-            for (pat, body, span) in bound_values {
-                let body = self.check(Because::Annotation(pat.span), body, pat.data.clone());
-                values.push(ValueDef { span, pat, body });
+            for (pat, value) in bound_values {
+                let body = self.check(Because::Annotation(pat.span), &value.bind, pat.data.clone());
+                values.push(ValueDef {
+                    span: value.span,
+                    pat,
+                    implicits: value.implicits.clone(),
+                    body,
+                });
             }
 
             // solve constraints
@@ -165,13 +170,20 @@ impl<'a> Typer<'a> {
 
                 constraint_count = self.constraints.len();
             }
-
-            todo!()
         }
 
         Decls::new(self.common_db(), values)
     }
 
+    /// Returns true if the given name is defined as a numeric type.
+    fn is_numeric(&self, name: &Name) -> bool {
+        match self.definitions.get(name) {
+            Some(Type::Name(name)) => self.is_numeric(name),
+            Some(Type::Range(..)) | Some(Type::Invalid) => true,
+            _ => false,
+        }
+    }
+
     fn common_db(&self) -> &'a dyn zippy_common::Db {
         <dyn Db as salsa::DbWithJar<zippy_common::Jar>>::as_jar_db(self.db)
     }
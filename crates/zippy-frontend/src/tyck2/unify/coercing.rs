@@ -19,7 +19,8 @@ impl Solver<'_> {
             // Either runtime checks or post-partial evaluation checks should
             // actually verify that the target type is wider than the source
             // type.
-            (Type::Range(lo1, hi1), Type::Range(lo2, hi2)) if lo1 == lo2 && hi1 == hi2 => {}
+            (Type::Range(lo1, hi1, step1), Type::Range(lo2, hi2, step2))
+                if lo1 == lo2 && hi1 == hi2 && step1 == step2 => {}
             (Type::Range(..), Type::Range(..)) => {
                 self.equal = false;
             }
@@ -33,6 +34,8 @@ impl Solver<'_> {
                 self.equal = false;
             }
 
+            (Type::Text, Type::Text) => {}
+
             // Coercing with names from generic instantiations.
             // See the note in [`Self::unify`] for why this is necessary.
             (Type::Name(n), u) if left.contains_key(&n) => {
@@ -11,6 +11,7 @@ impl Solver<'_> {
             (Type::Name(n), Type::Name(m)) if n == m => {}
             (Type::Range(..), Type::Range(..)) => {}
             (Type::Number, Type::Number) => {}
+            (Type::Text, Type::Text) => {}
             (Type::Type, Type::Type) => {}
 
             // Unifying with names from generic instantiations.
@@ -190,7 +190,12 @@ impl<'a> Solver<'a> {
 /// Returns `true` if the given variable occurs anywhere in the given type.
 fn occurs(var: &UniVar, ty: &Type) -> bool {
     match ty {
-        Type::Name(_) | Type::Range(..) | Type::Number | Type::Type | Type::Invalid => false,
+        Type::Name(_)
+        | Type::Range(..)
+        | Type::Number
+        | Type::Text
+        | Type::Type
+        | Type::Invalid => false,
         Type::Fun(t, u) | Type::Product(t, u) => occurs(var, t) || occurs(var, u),
 
         Type::Instantiated(ty, map) => occurs(var, ty) || map.values().any(|ty| occurs(var, ty)),
@@ -4,7 +4,7 @@
 //! the name resolution pass to take these names and figure out what they refer
 //! to.
 
-use zippy_common::hir::BindId;
+use zippy_common::hir::{BindId, Extern};
 use zippy_common::message::Span;
 use zippy_common::Number;
 
@@ -22,6 +22,25 @@ pub struct Decls {
 
     #[return_ref]
     pub types: Vec<TypeDef>,
+
+    /// The name of every imported module.
+    #[return_ref]
+    pub imports: Vec<(Name, Span)>,
+
+    #[return_ref]
+    pub tests: Vec<TestDef>,
+
+    /// The name of every function declared with `export fun`.
+    #[return_ref]
+    pub exports: Vec<(Name, Span)>,
+}
+
+/// A test, whose body is given by the value definition with the same name.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TestDef {
+    pub span: Span,
+    pub name: Name,
+    pub description: String,
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -31,6 +50,9 @@ pub struct TypeDef {
     pub pat: Pat,
     pub anno: Type,
     pub bind: Type,
+
+    /// The documentation comment right before the definition, if any.
+    pub doc: Option<String>,
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -41,6 +63,9 @@ pub struct ValueDef {
     pub implicits: Vec<(Name, Span)>,
     pub anno: Type,
     pub bind: Expr,
+
+    /// The documentation comment right before the definition, if any.
+    pub doc: Option<String>,
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -53,6 +78,7 @@ pub struct Expr {
 pub enum ExprNode {
     Name(Name),
     Num(Number),
+    Str(String),
 
     Lam(BindId, Pat, Box<Expr>),
     App(Box<Expr>, Box<Expr>),
@@ -60,8 +86,15 @@ pub enum ExprNode {
 
     Tuple(Box<Expr>, Box<Expr>),
 
+    /// The negation of a number. Negated number literals are folded into the
+    /// literal instead.
+    Neg(Box<Expr>),
+
     Anno(Box<Expr>, Type),
 
+    /// The body of an external function, which calls the C function with the
+    /// parameters of the functions it is nested in.
+    Extern(Extern),
     Hole,
     Invalid,
 }
@@ -90,10 +123,12 @@ pub struct Type {
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum TypeNode {
     Name(Name),
-    Range(Box<Expr>, Box<Expr>),
+    /// A range from one bound to another, with an optional resolution.
+    Range(Box<Expr>, Box<Expr>, Option<Box<Expr>>),
     Fun(Box<Type>, Box<Type>),
     Product(Box<Type>, Box<Type>),
     Type,
+    Text,
     Wildcard,
     Invalid,
 }
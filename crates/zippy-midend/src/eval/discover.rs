@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use zippy_common::mir::{
     Block, BranchNode, StaticValueNode, StmtNode, Type, TypeId, Types, Value, ValueNode,
 };
@@ -10,9 +12,16 @@ impl<D: Driver> Interpreter<'_, D> {
     /// Find all the blocks and values reachable from the entry point, and
     /// construct a worklist of items to be partially evaluated.
     pub fn discover_from_entry(&mut self, entry: Name) {
+        // Names reachable from an earlier entry point have been discovered
+        // already.
+        let discovered = self.worklist.clone();
         let mut worklist = vec![entry];
 
         while let Some(name) = worklist.pop() {
+            if discovered.contains(&name) {
+                continue;
+            }
+
             if self.worklist.contains(&name) {
                 panic!("cycle! what to do");
             }
@@ -37,6 +46,32 @@ impl<D: Driver> Interpreter<'_, D> {
         }
     }
 
+    /// Find every function which calls an external function, either directly
+    /// or through other functions. Since external functions may have any
+    /// effect, calls to these functions are left for the generated code to
+    /// make.
+    pub fn discover_impure(&mut self) {
+        self.impure.extend(self.decls.externs.keys().copied());
+
+        loop {
+            let impure: Vec<_> = self
+                .decls
+                .functions
+                .iter()
+                .filter(|(name, (_, block))| {
+                    !self.impure.contains(*name) && calls_any(block, &self.impure)
+                })
+                .map(|(name, _)| *name)
+                .collect();
+
+            if impure.is_empty() {
+                break;
+            }
+
+            self.impure.extend(impure);
+        }
+    }
+
     /// Collect all the blocks and values in the program, and construct a
     /// worklist of items to be partially evaluated.
     pub fn discover_all(&mut self) {
@@ -65,6 +100,18 @@ impl<D: Driver> Interpreter<'_, D> {
                     None
                 }
 
+                StaticValueNode::Text(text) => {
+                    self.globals.insert(
+                        *name,
+                        Value {
+                            node: ValueNode::Text(text),
+                            span: value.span,
+                            ty: value.ty,
+                        },
+                    );
+                    None
+                }
+
                 StaticValueNode::LateInit(block) => {
                     self.blocks.insert(*name, block);
                     self.blocks.get(name)
@@ -76,11 +123,18 @@ impl<D: Driver> Interpreter<'_, D> {
     }
 }
 
+fn calls_any(block: &Block, functions: &HashSet<Name>) -> bool {
+    block.stmts.iter().any(|stmt| match &stmt.node {
+        StmtNode::Apply { fun, .. } => functions.contains(fun),
+        _ => false,
+    })
+}
+
 fn visit_block(types: &Types, worklist: &mut Vec<Name>, block: &Block) {
     fn name_of_value(value: &Value) -> Option<Name> {
         match &value.node {
             ValueNode::Name(name) => Some(*name),
-            ValueNode::Num(_) | ValueNode::Invalid => None,
+            ValueNode::Num(_) | ValueNode::Text(_) | ValueNode::Invalid => None,
         }
     }
 
@@ -100,6 +154,8 @@ fn visit_block(types: &Types, worklist: &mut Vec<Name>, block: &Block) {
                 worklist.push(*of);
             }
 
+            StmtNode::Neg { of, .. } => worklist.push(*of),
+
             StmtNode::Function { .. } => todo!(),
             StmtNode::Join { .. } => todo!(),
             StmtNode::Proj { .. } => todo!(),
@@ -129,11 +185,13 @@ fn visit_type(types: &Types, worklist: &mut Vec<Name>, ty: &TypeId) {
             }
         }
 
-        Type::Range(lo, hi) => {
+        Type::Range(lo, hi, step) => {
             worklist.extend([*lo, *hi]);
+            worklist.extend(*step);
         }
 
         Type::Invalid => {}
         Type::Number => {}
+        Type::Text => {}
     }
 }
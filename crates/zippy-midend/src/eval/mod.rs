@@ -53,7 +53,7 @@ mod state;
 mod step;
 mod value;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use log::{info, trace};
 use zippy_common::message::Messages;
@@ -72,13 +72,14 @@ pub fn evaluate(
     context: &Context,
     names: &Names,
     types: &Types,
-    entry: Option<Name>,
+    roots: &[Name],
     decls: Decls,
 ) -> Decls {
     info!("beginning evaluation");
 
     let mut interp = Interpreter::new(driver, context, names, types, decls);
-    interp.discover(entry);
+    interp.discover_impure();
+    interp.discover(roots);
     trace!("discovery done");
 
     interp.run();
@@ -104,14 +105,18 @@ struct Interpreter<'a, D> {
 
     worklist: Vec<Name>,
     frames: Vec<Frame>,
-    globals: HashMap<Name, Value>,
+    globals: BTreeMap<Name, Value>,
     index: usize,
 
-    blocks: HashMap<Name, Block>,
+    blocks: BTreeMap<Name, Block>,
     functions: HashMap<Name, Vec<Name>>,
     redoing: HashMap<Name, Vec<Statement>>,
     frozen: HashSet<Name>,
 
+    /// Functions which call external functions, directly or indirectly. Calls
+    /// to these are never evaluated.
+    impure: HashSet<Name>,
+
     messages: Messages,
 }
 
@@ -133,24 +138,28 @@ impl<'a, D: Driver> Interpreter<'a, D> {
 
             worklist: Vec::new(),
             frames: Vec::new(),
-            globals: HashMap::new(),
+            globals: BTreeMap::new(),
             index: 0,
 
-            blocks: HashMap::new(),
+            blocks: BTreeMap::new(),
             functions: HashMap::new(),
             redoing: HashMap::new(),
             frozen: HashSet::new(),
 
+            impure: HashSet::new(),
+
             messages: Messages::new(),
         }
     }
 
-    pub fn discover(&mut self, entry: Option<Name>) {
-        if let Some(entry) = entry {
-            self.discover_from_entry(entry);
-        } else {
+    pub fn discover(&mut self, roots: &[Name]) {
+        if roots.is_empty() {
             trace!("no entry point; discovering everything");
             self.discover_all();
+        } else {
+            for root in roots {
+                self.discover_from_entry(*root);
+            }
         }
     }
 
@@ -179,12 +188,14 @@ impl<'a, D: Driver> Interpreter<'a, D> {
 
     pub fn collect(mut self) -> (Decls, Messages) {
         let mut res = Decls::new(Vec::new());
+        res.externs = std::mem::take(&mut self.decls.externs);
 
         for (name, value) in self.globals {
             let Value { span, ty, .. } = value;
 
             let node = match value.node {
                 ValueNode::Num(i) => StaticValueNode::Num(i),
+                ValueNode::Text(text) => StaticValueNode::Text(text),
                 _ => {
                     let node = BranchNode::Return(vec![value]);
                     let branch = Branch { node, span, ty };
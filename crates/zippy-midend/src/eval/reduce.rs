@@ -17,7 +17,7 @@ pub struct ReduceResult {
 impl<D: Driver> Interpreter<'_, D> {
     pub fn reduce_value(&self, value: &Value) -> Option<ReducedValue> {
         match &value.node {
-            ValueNode::Num(_) | ValueNode::Invalid => {
+            ValueNode::Num(_) | ValueNode::Text(_) | ValueNode::Invalid => {
                 Some(self.locally_static_value(value.clone()))
             }
 
@@ -58,6 +58,11 @@ impl<D: Driver> Interpreter<'_, D> {
                 self.reduce_coerce(name, (args.remove(0), of), from, to, stmt.span)
             }
 
+            StmtNode::Neg { name, of } => {
+                let mut args = args;
+                self.reduce_neg(name, (args.remove(0), of), stmt.span, stmt.ty)
+            }
+
             StmtNode::Apply {
                 names,
                 fun,
@@ -103,10 +108,10 @@ impl<D: Driver> Interpreter<'_, D> {
         }
     }
 
-    /// Partially evaluate a call. If the provided function is unknown, the
-    /// instruction is left mostly as is. Otherwise, a call is made. If all of
-    /// the arguments (including the function) are statically known and all
-    /// effects are handled, no instruction will be produced.
+    /// Partially evaluate a call. If the provided function is unknown or
+    /// impure, the instruction is left mostly as is. Otherwise, a call is
+    /// made. If all of the arguments (including the function) are statically
+    /// known and all effects are handled, no instruction will be produced.
     fn reduce_call(
         &mut self,
         names: Vec<Name>,
@@ -127,7 +132,7 @@ impl<D: Driver> Interpreter<'_, D> {
         };
 
         let (push_inst, action) = match (self.place_of(&new_fun), self.functions.get(&new_fun)) {
-            (Some(place), Some(params)) => {
+            (Some(place), Some(params)) if !self.impure.contains(&new_fun) => {
                 assert_eq!(params.len(), args.1.len());
 
                 let mut new_env = Env::new();
@@ -235,4 +240,61 @@ impl<D: Driver> Interpreter<'_, D> {
             values: Some(vec![reduced]),
         }
     }
+
+    /// Reduce a negation. Known numbers are negated right away, while anything
+    /// else leaves the negation to the generated code.
+    fn reduce_neg(
+        &mut self,
+        name: Name,
+        of: (ReducedValue, Name),
+        span: Span,
+        ty: TypeId,
+    ) -> ReduceResult {
+        let (reduced, unreduced) = of;
+
+        let of = if reduced.is_dynamic(self.frame_index()) {
+            unreduced
+        } else {
+            match reduced.value.node {
+                ValueNode::Num(i) => {
+                    let value = Value {
+                        node: ValueNode::Num(-i),
+                        span,
+                        ty,
+                    };
+
+                    return ReduceResult {
+                        action: Action::None,
+                        operation: None,
+                        values: Some(vec![ReducedValue {
+                            value,
+                            frame: reduced.frame,
+                        }]),
+                    };
+                }
+
+                ValueNode::Name(of) => of,
+
+                ValueNode::Text(_) | ValueNode::Invalid => {
+                    return ReduceResult {
+                        action: Action::None,
+                        operation: None,
+                        values: Some(vec![reduced]),
+                    };
+                }
+            }
+        };
+
+        let operation = Statement {
+            node: StmtNode::Neg { name, of },
+            span,
+            ty,
+        };
+
+        ReduceResult {
+            action: Action::None,
+            operation: Some(Operation::Statement(operation)),
+            values: None,
+        }
+    }
 }
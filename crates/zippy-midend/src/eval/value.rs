@@ -88,7 +88,7 @@ impl<D: Driver> Interpreter<'_, D> {
                 StmtNode::Function { .. } => todo!(),
                 StmtNode::Join { .. } => todo!(),
 
-                StmtNode::Proj { of, .. } => {
+                StmtNode::Proj { of, .. } | StmtNode::Neg { of, .. } => {
                     let span = stmt.span;
                     let ty = self.context.get(of);
                     vec![Value {
@@ -116,7 +116,7 @@ impl<D: Driver> Interpreter<'_, D> {
                 StmtNode::Function { .. } => todo!(),
                 StmtNode::Join { .. } => todo!(),
 
-                StmtNode::Proj { name, .. } => vec![*name],
+                StmtNode::Proj { name, .. } | StmtNode::Neg { name, .. } => vec![*name],
                 StmtNode::Tuple { name, .. } => vec![*name],
             },
         }
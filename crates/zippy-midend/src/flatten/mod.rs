@@ -49,7 +49,16 @@ impl<'a> Flattener<'a> {
             });
         }
 
-        Decls::new(defs)
+        for name in decls.externs.keys() {
+            let ty = self.context.get(name);
+            let ty = self.flatten_type(&ty);
+            self.context.replace(*name, ty);
+        }
+
+        Decls {
+            externs: decls.externs,
+            ..Decls::new(defs)
+        }
     }
 
     fn flatten_def(&mut self, name: &Name, bind: Block) -> Block {
@@ -137,6 +146,11 @@ impl<'a> Flattener<'a> {
                     StmtNode::Coerce { name, of, from, to }
                 }
 
+                StmtNode::Neg { name, of } => {
+                    let of = self.flatten_name(of);
+                    StmtNode::Neg { name, of }
+                }
+
                 StmtNode::Join { name, param, body } => {
                     let body = self.flatten_def(&name, body);
                     StmtNode::Join { name, param, body }
@@ -238,7 +252,9 @@ impl<'a> Flattener<'a> {
                 names
             }
 
-            Type::Invalid | Type::Number | Type::Fun(..) | Type::Range(..) => vec![name],
+            Type::Invalid | Type::Number | Type::Text | Type::Fun(..) | Type::Range(..) => {
+                vec![name]
+            }
         }
     }
 
@@ -257,6 +273,7 @@ impl<'a> Flattener<'a> {
                 (Some(_), Some(_)) => unreachable!(),
             },
             ValueNode::Num(i) => ValueNode::Num(i),
+            ValueNode::Text(text) => ValueNode::Text(text),
             ValueNode::Invalid => ValueNode::Invalid,
         };
 
@@ -294,6 +311,7 @@ impl<'a> Flattener<'a> {
                 vec![self.types.add(Type::Fun(ts, us))]
             }
             Type::Number => vec![*ty],
+            Type::Text => vec![*ty],
             Type::Invalid => vec![*ty],
         }
     }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use im::HashSet;
 
@@ -6,8 +6,13 @@ use zippy_common::message::Span;
 use zippy_common::mir::{Block, BranchNode, Decls, StmtNode, ValueNode};
 use zippy_common::names::Name;
 
-pub fn free_vars(decls: &Decls) -> HashMap<Name, Vec<(Name, Span)>> {
-    let global = decls.defs.iter().map(|def| def.name).collect();
+pub fn free_vars(decls: &Decls) -> BTreeMap<Name, Vec<(Name, Span)>> {
+    let global = decls
+        .defs
+        .iter()
+        .map(|def| def.name)
+        .chain(decls.externs.keys().copied())
+        .collect();
     let mut freer = Freer::new(global);
     freer.calculate_free(decls);
 
@@ -16,14 +21,14 @@ pub fn free_vars(decls: &Decls) -> HashMap<Name, Vec<(Name, Span)>> {
 
 #[derive(Debug)]
 struct Freer {
-    funs: HashMap<Name, Vec<(Name, Span)>>,
+    funs: BTreeMap<Name, Vec<(Name, Span)>>,
     global: HashSet<Name>,
 }
 
 impl Freer {
     pub fn new(global: HashSet<Name>) -> Self {
         Self {
-            funs: HashMap::new(),
+            funs: BTreeMap::new(),
             global,
         }
     }
@@ -110,7 +115,7 @@ impl Freer {
                     bound.insert(*name);
                 }
 
-                StmtNode::Coerce { name, of, .. } => {
+                StmtNode::Coerce { name, of, .. } | StmtNode::Neg { name, of } => {
                     if !bound.contains(of) && free.insert(*of).is_none() {
                         res.push((*of, expr.span));
                     }
@@ -1,7 +1,8 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use log::{debug, trace};
 
+use zippy_common::hir::Extern;
 use zippy_common::message::{Messages, Span};
 use zippy_common::mir::{
     Block, Branch, BranchNode, Context, Decls, Statement, StaticValue, StaticValueNode, StmtNode,
@@ -27,15 +28,16 @@ pub fn hoist(
         _names: names,
         _context: context,
 
-        functions: HashMap::new(),
-        values: HashMap::new(),
+        functions: BTreeMap::new(),
+        values: BTreeMap::new(),
     };
 
-    hoister.hoist_decls(decls);
+    let externs = hoister.hoist_decls(decls);
     let res = Decls {
         defs: Vec::new(),
         functions: hoister.functions,
         values: hoister.values,
+        externs,
     };
 
     trace!("done hoisting");
@@ -48,12 +50,14 @@ pub struct Hoist<'a, D> {
     _names: &'a mut Names,
     _context: &'a mut Context,
 
-    functions: HashMap<Name, (Vec<Name>, Block)>,
-    values: HashMap<Name, StaticValue>,
+    functions: BTreeMap<Name, (Vec<Name>, Block)>,
+    values: BTreeMap<Name, StaticValue>,
 }
 
 impl<D: Driver> Hoist<'_, D> {
-    fn hoist_decls(&mut self, decls: Decls) {
+    /// Hoist every definition to the top level, returning the external
+    /// functions, which need no hoisting.
+    fn hoist_decls(&mut self, decls: Decls) -> BTreeMap<Name, Extern> {
         let free_vars = free_vars(&decls);
         let mut messages = Messages::new();
 
@@ -68,12 +72,14 @@ impl<D: Driver> Hoist<'_, D> {
         }
 
         self.driver.report(messages);
+
+        decls.externs
     }
 
     fn hoist_value(
         &mut self,
         name_for: Name,
-        free_vars: &HashMap<Name, Vec<(Name, Span)>>,
+        free_vars: &BTreeMap<Name, Vec<(Name, Span)>>,
         exprs: Block,
     ) {
         let mut init = Vec::with_capacity(exprs.stmts.len());
@@ -109,6 +115,7 @@ impl<D: Driver> Hoist<'_, D> {
                     let ty = value.ty;
                     let node = match value.node {
                         ValueNode::Num(i) => StaticValueNode::Num(i),
+                        ValueNode::Text(text) => StaticValueNode::Text(text),
                         ValueNode::Invalid => {
                             let node = BranchNode::Return(vec![value]);
                             let branch = Branch { node, span, ty };
@@ -149,7 +156,7 @@ impl<D: Driver> Hoist<'_, D> {
 
     fn hoist_function(
         &mut self,
-        free_vars: &HashMap<Name, Vec<(Name, Span)>>,
+        free_vars: &BTreeMap<Name, Vec<(Name, Span)>>,
         exprs: Block,
     ) -> Block {
         let mut res = Vec::with_capacity(exprs.stmts.len());
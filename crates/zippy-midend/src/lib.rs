@@ -3,22 +3,28 @@ mod flatten;
 mod hoist;
 mod lower;
 
+use std::time::Instant;
+
 use log::{debug, info, trace};
 
+use zippy_common::mir::dot::dot;
 use zippy_common::mir::pretty::Prettier;
-use zippy_common::mir::{self, check};
+use zippy_common::mir::{self, check, BranchNode, StaticValueNode, Value, ValueNode};
 use zippy_common::names::{Name, Names};
 use zippy_common::thir::TypeckResult;
-use zippy_common::{Driver, EvalAmount, IrOutput};
+use zippy_common::{Driver, EvalAmount, IrOutput, Number, Pass, Stat};
 
+/// Lower and partially evaluate a program. Only the names reachable from the
+/// given roots (usually just the entry point) are evaluated.
 pub fn elaborate(
     driver: &mut impl Driver,
     names: &mut Names,
     tyckres: TypeckResult,
-    entry: Option<Name>,
+    roots: &[Name],
 ) -> (mir::Types, mir::Context, mir::Decls) {
     info!("beginning elaboration");
 
+    let start = Instant::now();
     let (mut types, mut context, res) = lower::lower(
         driver,
         &tyckres.subst,
@@ -28,6 +34,8 @@ pub fn elaborate(
         tyckres.decls,
     );
 
+    driver.report_pass(Pass::Lower, start.elapsed());
+
     let mut error = check(names, &types, &context, &res);
     if error {
         eprintln!("error during lowering");
@@ -40,7 +48,12 @@ pub fn elaborate(
         prettier.pretty_all(&res)
     });
 
+    driver.output_ir(IrOutput::MirDot("lowering"), || dot(names, &types, &res));
+
+    let start = Instant::now();
     let res = flatten::flatten(names, &mut types, &mut context, res);
+    driver.report_pass(Pass::Flatten, start.elapsed());
+
     if !error {
         error = check(names, &types, &context, &res);
         if error {
@@ -55,7 +68,12 @@ pub fn elaborate(
         prettier.pretty_all(&res)
     });
 
+    driver.output_ir(IrOutput::MirDot("flattening"), || dot(names, &types, &res));
+
+    let start = Instant::now();
     let res = hoist::hoist(driver, names, &mut context, res);
+    driver.report_pass(Pass::Hoist, start.elapsed());
+
     if !error {
         error = check(names, &types, &context, &res);
         if error {
@@ -70,9 +88,15 @@ pub fn elaborate(
         prettier.pretty_all(&res)
     });
 
+    driver.output_ir(IrOutput::MirDot("hoisting"), || dot(names, &types, &res));
+
+    driver.report_stat(Stat::StmtsBeforeEval, res.statements());
+
     let res = match driver.eval_amount() {
         EvalAmount::Full => {
-            let res = eval::evaluate(driver, &context, names, &types, entry, res);
+            let start = Instant::now();
+            let res = eval::evaluate(driver, &context, names, &types, roots, res);
+            driver.report_pass(Pass::Eval, start.elapsed());
 
             if !error {
                 error = check(names, &types, &context, &res);
@@ -97,7 +121,65 @@ pub fn elaborate(
         prettier.pretty_all(&res)
     });
 
+    driver.output_ir(IrOutput::MirDot("evaluation"), || dot(names, &types, &res));
+
+    driver.report_stat(Stat::StmtsAfterEval, res.statements());
+    driver.report_stat(Stat::Types, types.len());
+
     trace!("done elaborating");
 
     (types, context, res)
 }
+
+/// Get the outcome of a test if the partial evaluator was able to reduce it
+/// completely, where `true` means the test passed. A test passes if it
+/// evaluates to zero. Returns `None` if the test has to be run to find out.
+pub fn test_outcome(decls: &mir::Decls, test: &Name) -> Option<bool> {
+    let value = decls.values.get(test)?;
+    let block = match &value.node {
+        StaticValueNode::Num(n) => return Some(*n == Number::from(0)),
+        StaticValueNode::Text(_) => return None,
+        StaticValueNode::LateInit(block) => block,
+    };
+
+    match (&block.stmts[..], &block.branch.node) {
+        ([], BranchNode::Return(values)) => match &values[..] {
+            [Value {
+                node: ValueNode::Num(n),
+                ..
+            }] => Some(*n == Number::from(0)),
+
+            [Value {
+                node: ValueNode::Invalid,
+                ..
+            }] => Some(false),
+
+            _ => None,
+        },
+
+        _ => None,
+    }
+}
+
+/// Get the values a top-level value was reduced to, if the partial evaluator
+/// was able to reduce it completely.
+pub fn static_value(decls: &mir::Decls, name: &Name) -> Option<Vec<Number>> {
+    let value = decls.values.get(name)?;
+    let block = match &value.node {
+        StaticValueNode::Num(n) => return Some(vec![n.clone()]),
+        StaticValueNode::Text(_) => return None,
+        StaticValueNode::LateInit(block) => block,
+    };
+
+    match (&block.stmts[..], &block.branch.node) {
+        ([], BranchNode::Return(values)) => values
+            .iter()
+            .map(|value| match &value.node {
+                ValueNode::Num(n) => Some(n.clone()),
+                ValueNode::Text(_) | ValueNode::Name(_) | ValueNode::Invalid => None,
+            })
+            .collect(),
+
+        _ => None,
+    }
+}
@@ -1,5 +1,5 @@
 use zippy_common::mir::pretty::Prettier;
-use zippy_common::mir::{Block, Branch, BranchNode, Statement, StmtNode, Value, ValueNode};
+use zippy_common::mir::{Block, Branch, BranchNode, Statement, StmtNode, Type, Value, ValueNode};
 use zippy_common::names::Name;
 
 use super::{HiExpr, HiExprNode, Inst, Lowerer};
@@ -39,6 +39,7 @@ impl Lowerer<'_> {
 
         let node = match expr.node {
             HiExprNode::Num(i) => ValueNode::Num(i),
+            HiExprNode::Str(text) => ValueNode::Text(text),
             HiExprNode::Name(name) => ValueNode::Name(name),
             HiExprNode::Invalid => ValueNode::Invalid,
             HiExprNode::Hole => {
@@ -50,6 +51,40 @@ impl Lowerer<'_> {
                 ValueNode::Invalid
             }
 
+            // The C function is called with every parameter of the function
+            // it is the body of, even if it is curried.
+            HiExprNode::Extern(external) => {
+                let args: Vec<_> = self
+                    .params
+                    .iter()
+                    .map(|param| Value {
+                        node: ValueNode::Name(*param),
+                        span,
+                        ty: self.context.get(param),
+                    })
+                    .collect();
+
+                let fun_ty = Type::Fun(args.iter().map(|arg| arg.ty).collect(), vec![ty]);
+                let fun_ty = self.types.add(fun_ty);
+                let fun = self.fresh_name(span, ctx, fun_ty);
+                self.externs.insert(fun, external);
+
+                let name = self.fresh_name(span, ctx, ty);
+                let expr = StmtNode::Apply {
+                    names: vec![name],
+                    fun,
+                    args,
+                };
+                let expr = Statement {
+                    ty,
+                    span,
+                    node: expr,
+                };
+
+                within.push(expr);
+                ValueNode::Name(name)
+            }
+
             HiExprNode::Tuple(x, y) => {
                 let x = self.make_value(inst, ctx, within, *x);
                 let y = self.make_value(inst, ctx, within, *y);
@@ -94,17 +129,20 @@ impl Lowerer<'_> {
                     }
 
                     ValueNode::Invalid => ValueNode::Invalid,
-                    ValueNode::Num(_) => ValueNode::Invalid,
+                    ValueNode::Num(_) | ValueNode::Text(_) => ValueNode::Invalid,
                 }
             }
 
             HiExprNode::Lam(param, body) => {
                 let name = self.fresh_name(expr.span, ctx, ty);
 
-                let mut body = self.lower_expr(inst, name, *body);
-
                 // Insert parameter destructuring
                 let (param, mut destructuring) = self.destruct_local(inst, name, param);
+
+                self.params.push(param);
+                let mut body = self.lower_expr(inst, name, *body);
+                self.params.pop();
+
                 destructuring.extend(body.stmts);
                 body.stmts = destructuring;
 
@@ -164,7 +202,30 @@ impl Lowerer<'_> {
                     }
 
                     ValueNode::Invalid => ValueNode::Invalid,
-                    ValueNode::Num(_) => unreachable!(),
+                    ValueNode::Num(_) | ValueNode::Text(_) => unreachable!(),
+                }
+            }
+
+            HiExprNode::Neg(x) => {
+                let value = self.make_value(inst, ctx, within, *x);
+
+                match value.node {
+                    ValueNode::Num(i) => ValueNode::Num(-i),
+                    ValueNode::Name(of) => {
+                        let name = self.fresh_name(span, ctx, ty);
+
+                        let stmt = Statement {
+                            ty,
+                            span,
+                            node: StmtNode::Neg { name, of },
+                        };
+
+                        within.push(stmt);
+                        ValueNode::Name(name)
+                    }
+
+                    ValueNode::Invalid => ValueNode::Invalid,
+                    ValueNode::Text(_) => unreachable!(),
                 }
             }
 
@@ -3,10 +3,11 @@ mod expr;
 mod poly;
 mod types;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use log::{debug, trace};
 
+use zippy_common::hir::Extern;
 use zippy_common::message::{Messages, Span};
 use zippy_common::mir::{Context, Decls, TypeId, Types, ValueDef};
 use zippy_common::names::{Name, Names};
@@ -58,7 +59,12 @@ struct Lowerer<'a> {
     named_types: HashMap<Name, TypeId>,
     coercions: HiCoercions,
 
+    /// The parameters of the functions currently being lowered, innermost
+    /// last.
+    params: Vec<Name>,
+
     values: Vec<ValueDef>,
+    externs: BTreeMap<Name, Extern>,
 }
 
 impl<'a> Lowerer<'a> {
@@ -77,7 +83,10 @@ impl<'a> Lowerer<'a> {
             named_types: HashMap::new(),
             coercions,
 
+            params: Vec::new(),
+
             values: Vec::new(),
+            externs: BTreeMap::new(),
         }
     }
 
@@ -112,7 +121,9 @@ impl<'a> Lowerer<'a> {
             self.destruct_monomorphic(&inst, ctx, def.span, def.pat, bind);
         }
 
-        Decls::new(self.values.drain(..).collect())
+        let mut decls = Decls::new(self.values.drain(..).collect());
+        decls.externs = std::mem::take(&mut self.externs);
+        decls
     }
 
     fn fresh_name(&mut self, at: Span, ctx: Name, ty: TypeId) -> Name {
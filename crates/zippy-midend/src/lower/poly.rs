@@ -50,7 +50,11 @@ impl Lowerer<'_> {
         bind: HiExpr,
     ) -> HiExpr {
         let node = match bind.node {
-            node @ (HiExprNode::Num(_) | HiExprNode::Hole | HiExprNode::Invalid) => node,
+            node @ (HiExprNode::Num(_)
+            | HiExprNode::Str(_)
+            | HiExprNode::Extern(_)
+            | HiExprNode::Hole
+            | HiExprNode::Invalid) => node,
 
             HiExprNode::Name(name) => {
                 if let Some(new_name) = name_map.get(&name) {
@@ -66,6 +70,11 @@ impl Lowerer<'_> {
                 HiExprNode::Tuple(a, b)
             }
 
+            HiExprNode::Neg(x) => {
+                let x = Box::new(self.copy_expr(name_map, old_name, new_name, *x));
+                HiExprNode::Neg(x)
+            }
+
             HiExprNode::App(fun, arg) => {
                 let fun = Box::new(self.copy_expr(name_map, old_name, new_name, *fun));
                 let arg = Box::new(self.copy_expr(name_map, old_name, new_name, *arg));
@@ -33,7 +33,7 @@ impl Lowerer<'_> {
                 }
             }
 
-            HiType::Range(lo, hi) => Some(self.types.add(Type::Range(lo, hi))),
+            HiType::Range(lo, hi, step) => Some(self.types.add(Type::Range(lo, hi, step))),
 
             HiType::Product(t, u) => {
                 let t = self.try_lower_type(inst, *t)?;
@@ -51,6 +51,7 @@ impl Lowerer<'_> {
 
             HiType::Type => unreachable!(),
             HiType::Number => Some(self.types.add(Type::Number)),
+            HiType::Text => Some(self.types.add(Type::Text)),
             HiType::Invalid => Some(self.types.add(Type::Invalid)),
         }
     }
@@ -1,6 +1,11 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use clap::{ArgAction, Args, Parser, Subcommand};
+use anyhow::anyhow;
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+
+use super::input::Source;
+use super::manifest::{Eval, Manifest, MANIFEST_NAME};
+use super::message_format::MessageFormat;
 
 /// a smol functional/imperative programming language.
 #[derive(Debug, Parser)]
@@ -16,7 +21,40 @@ pub struct Arguments {
 
 impl Arguments {
     pub fn options(&self) -> &Options {
-        self.command.options()
+        self.command
+            .options()
+            .expect("this command does not take any options")
+    }
+
+    /// If no path was given, read the manifest in the current directory and
+    /// use the options it declares, unless they were given on the command line.
+    pub fn load_manifest(&mut self) -> anyhow::Result<()> {
+        let Some(opts) = self.command.options_mut() else {
+            return Ok(());
+        };
+
+        if opts.path.is_some() || opts.expr.is_some() {
+            return Ok(());
+        }
+
+        if !Path::new(MANIFEST_NAME).exists() {
+            return Err(anyhow!(
+                "no path given and no '{MANIFEST_NAME}' in the current directory"
+            ));
+        }
+
+        let manifest = Manifest::read(Path::new(""))?;
+
+        opts.path = Some(manifest.package.main.clone());
+        opts.target = opts.target.take().or_else(|| manifest.build.target.clone());
+        opts.artifacts = opts
+            .artifacts
+            .take()
+            .or_else(|| manifest.build.artifacts.clone());
+        opts.no_eval |= manifest.build.eval == Eval::None;
+        opts.manifest = Some(manifest);
+
+        Ok(())
     }
 }
 
@@ -29,17 +67,63 @@ pub enum Command {
     #[command(visible_alias = "c")]
     Check(Options),
 
+    /// Run every test in the given file.
+    #[command(visible_alias = "t")]
+    Test(Options),
+
+    /// Check the given file again whenever it or any file it imports changes.
+    #[command(visible_alias = "w")]
+    Watch(WatchOptions),
+
     #[command()]
     Clean(Options),
+
+    /// Write an HTML page documenting every definition in the given file and
+    /// the files it imports.
+    #[command()]
+    Doc(Options),
+
+    /// Format the given file in place.
+    #[command()]
+    Fmt(FormatOptions),
+
+    /// Show a detailed explanation of an error code.
+    #[command()]
+    Explain(ExplainOptions),
+
+    /// Start an interactive session for evaluating expressions.
+    #[command()]
+    Repl,
+
+    /// Start a language server communicating over stdio.
+    #[command()]
+    Lsp,
 }
 
 impl Command {
-    pub fn options(&self) -> &Options {
+    pub fn options(&self) -> Option<&Options> {
+        match self {
+            Self::Run(opts) => Some(opts),
+            Self::Build(opts) => Some(opts),
+            Self::Check(opts) => Some(opts),
+            Self::Test(opts) => Some(opts),
+            Self::Watch(opts) => Some(&opts.options),
+            Self::Clean(opts) => Some(opts),
+            Self::Doc(opts) => Some(opts),
+            Self::Fmt(_) | Self::Explain(_) | Self::Repl | Self::Lsp => None,
+        }
+    }
+
+    fn options_mut(&mut self) -> Option<&mut Options> {
         match self {
-            Self::Run(opts) => opts,
-            Self::Build(opts) => opts,
-            Self::Check(opts) => opts,
-            Self::Clean(opts) => opts,
+            Self::Run(opts) => Some(opts),
+            Self::Build(opts) => Some(opts),
+            Self::Check(opts) => Some(opts),
+            Self::Test(opts) => Some(opts),
+            Self::Watch(opts) => Some(&mut opts.options),
+            Self::Clean(opts) => Some(opts),
+            Self::Doc(opts) => Some(opts),
+            Self::Fmt(_) | Self::Explain(_) | Self::Repl | Self::Lsp => None,
         }
     }
 
@@ -54,9 +138,31 @@ impl Command {
     pub fn check(&self) -> bool {
         matches!(self, Self::Run(_) | Self::Build(_) | Self::Check(_))
     }
+
+    /// Returns `true` if this command works with a program which should have
+    /// an entry point, meaning an executable rather than a library.
+    pub fn needs_entry(&self) -> bool {
+        match self {
+            Self::Run(_) => true,
+            Self::Build(opts) | Self::Check(opts) => !opts.emit_kind().is_library(),
+            Self::Watch(opts) => !opts.options.emit_kind().is_library(),
+            _ => false,
+        }
+    }
+
+    pub fn test(&self) -> bool {
+        matches!(self, Self::Test(_))
+    }
+
+    pub fn watch(&self) -> Option<&WatchOptions> {
+        match self {
+            Self::Watch(opts) => Some(opts),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, Args)]
+#[derive(Debug, Default, Args)]
 pub struct Options {
     /// Completely disable partial evaluation.
     #[arg(long, action = ArgAction::SetTrue)]
@@ -66,16 +172,296 @@ pub struct Options {
     #[arg(long, action = ArgAction::SetTrue)]
     pub preserve_output: bool,
 
+    /// The same as `--emit mir`.
     #[arg(long, action = ArgAction::SetTrue)]
     pub output_ir: bool,
 
-    #[arg(long, default_value = "artifacts")]
-    pub artifacts: PathBuf,
+    /// Report how long each pass of the compiler takes, along with the size
+    /// of the program between passes.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub time_passes: bool,
+
+    /// The optimization level to build the generated code with.
+    #[arg(short = 'O', value_enum, default_value_t)]
+    pub opt_level: OptLevel,
+
+    /// Build with debug info pointing back at the source code.
+    #[arg(short = 'g', action = ArgAction::SetTrue)]
+    pub debug: bool,
+
+    /// What kind of output to build, along with any intermediate
+    /// representations to write out, separated by commas. Representations
+    /// are written to the artifacts directory, or to stdout if followed by
+    /// `=-`.
+    #[arg(long, value_delimiter = ',', value_parser = parse_emit)]
+    pub emit: Vec<EmitItem>,
+
+    /// How to report errors and other messages.
+    #[arg(long, value_enum, default_value_t)]
+    pub message_format: MessageFormat,
+
+    /// When to use colors in the compiler output.
+    #[arg(long, value_enum, default_value_t)]
+    pub color: Color,
+
+    /// How much of the source code to show in messages [default: rich, or
+    /// short with `--message-format short`]
+    #[arg(long, value_enum)]
+    pub diagnostic_style: Option<DiagnosticStyle>,
+
+    /// Only show the messages up to the first N errors, and summarize the
+    /// rest.
+    #[arg(long, value_name = "N")]
+    pub error_limit: Option<usize>,
+
+    /// Where to put the generated files [default: artifacts]
+    #[arg(long)]
+    pub artifacts: Option<PathBuf>,
 
     /// The target to compile the code for.
     #[arg(short, long)]
     pub target: Option<String>,
 
+    /// The file to compile, or `-` to read the code from stdin. If left out,
+    /// the manifest in the current directory is used.
+    pub path: Option<PathBuf>,
+
+    /// Compile the given code instead of a file.
+    #[arg(short, long = "expr", value_name = "CODE", conflicts_with = "path")]
+    pub expr: Option<String>,
+
+    /// The manifest the other options were taken from, if any.
+    #[arg(skip)]
+    pub manifest: Option<Manifest>,
+}
+
+impl Options {
+    pub fn path(&self) -> &Path {
+        self.path
+            .as_deref()
+            .expect("the manifest should have been loaded")
+    }
+
+    pub fn artifacts(&self) -> &Path {
+        self.artifacts
+            .as_deref()
+            .unwrap_or_else(|| Path::new("artifacts"))
+    }
+
+    /// What kind of output to build. If several are given, the last one wins.
+    pub fn emit_kind(&self) -> Emit {
+        self.emit
+            .iter()
+            .rev()
+            .find_map(|item| match item {
+                EmitItem::Output(emit) => Some(*emit),
+                EmitItem::Ir { .. } => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// The intermediate representations to write out, each along with whether
+    /// it goes to stdout rather than the artifacts directory.
+    pub fn ir_stages(&self) -> impl Iterator<Item = (IrStage, bool)> + '_ {
+        self.emit.iter().filter_map(|item| match item {
+            EmitItem::Ir { stage, stdout } => Some((*stage, *stdout)),
+            EmitItem::Output(_) => None,
+        })
+    }
+
+    pub fn source(&self) -> Source<'_> {
+        if let Some(expr) = &self.expr {
+            return Source::Expr(expr);
+        }
+
+        match self.path() {
+            path if path == Path::new("-") => Source::Stdin,
+            path => Source::File(path),
+        }
+    }
+
+    /// The name of the compiled program, which is the name of the package if
+    /// there is a manifest and the name of the source file otherwise. Code
+    /// which isn't in a file gives a program named `stdin` or `expr`.
+    pub fn output(&self) -> PathBuf {
+        match (&self.manifest, self.source()) {
+            (Some(manifest), _) => PathBuf::from(&manifest.package.name),
+            (None, Source::File(path)) => path.to_path_buf(),
+            (None, Source::Stdin) => PathBuf::from("stdin"),
+            (None, Source::Expr(_)) => PathBuf::from("expr"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum Emit {
+    /// An executable.
+    #[default]
+    Exe,
+
+    /// An object file.
+    Obj,
+
+    /// A static library.
+    Staticlib,
+
+    /// A dynamic library which can be loaded from C.
+    Cdylib,
+}
+
+impl Emit {
+    /// Returns `true` if this output exports functions rather than having an
+    /// entry point.
+    pub fn is_library(&self) -> bool {
+        !matches!(self, Self::Exe)
+    }
+}
+
+/// A stage of the compiler whose intermediate representation can be written
+/// out with `--emit`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, ValueEnum)]
+pub enum IrStage {
+    /// The tokens of every file, with their spans.
+    Tokens,
+
+    /// The concrete syntax tree of every file.
+    Cst,
+
+    /// The declarations of every file before names are resolved.
+    Hir,
+
+    /// The declarations of the program after names are resolved.
+    Resolved,
+
+    /// The dependency graph between definitions, as a Graphviz graph.
+    DepsDot,
+
+    /// The kind of every type definition.
+    Kinds,
+
+    /// The type of every value definition after typechecking.
+    Thir,
+
+    /// The MIR after each midend pass.
+    Mir,
+
+    /// The control flow of the MIR after each midend pass, as a Graphviz
+    /// graph.
+    MirDot,
+
+    /// The generated C code.
+    C,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EmitItem {
+    Output(Emit),
+    Ir { stage: IrStage, stdout: bool },
+}
+
+fn parse_emit(item: &str) -> Result<EmitItem, String> {
+    let (name, to) = match item.split_once('=') {
+        Some((name, to)) => (name, Some(to)),
+        None => (item, None),
+    };
+
+    let stdout = match to {
+        None => false,
+        Some("-") => true,
+        Some(to) => return Err(format!("can only write to '-' (stdout), not '{to}'")),
+    };
+
+    if let Ok(stage) = IrStage::from_str(name, true) {
+        return Ok(EmitItem::Ir { stage, stdout });
+    }
+
+    match Emit::from_str(name, true) {
+        Ok(emit) if !stdout => Ok(EmitItem::Output(emit)),
+        Ok(_) => Err(format!("'{name}' can't be written to stdout")),
+        Err(_) => Err(format!("unknown output '{name}'")),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum Color {
+    /// Use colors if writing to a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum DiagnosticStyle {
+    /// Show the source code around every label.
+    Rich,
+
+    /// Show the message and notes, but not the source code.
+    Medium,
+
+    /// Show a single line for every message.
+    Short,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum OptLevel {
+    #[default]
+    #[value(name = "0")]
+    None,
+
+    #[value(name = "1")]
+    Less,
+
+    #[value(name = "2")]
+    Default,
+
+    #[value(name = "3")]
+    Aggressive,
+
+    /// Optimize for size.
+    #[value(name = "s")]
+    Size,
+}
+
+impl OptLevel {
+    /// The level as given to the C compiler.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "0",
+            Self::Less => "1",
+            Self::Default => "2",
+            Self::Aggressive => "3",
+            Self::Size => "s",
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct FormatOptions {
+    /// Only check whether the file is formatted, without changing it.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub check: bool,
+
     #[arg(required = true)]
     pub path: PathBuf,
 }
+
+#[derive(Debug, Args)]
+pub struct ExplainOptions {
+    /// The code to explain, such as `ET05`.
+    pub code: String,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchOptions {
+    /// Also build the program after every change.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub build: bool,
+
+    /// Also build and run the program after every change.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub run: bool,
+
+    #[command(flatten)]
+    pub options: Options,
+}
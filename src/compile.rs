@@ -1,41 +1,36 @@
+use std::collections::hash_map::DefaultHasher;
 use std::env;
-use std::ffi::OsStr;
-use std::fs::{DirBuilder, File};
+use std::ffi::{OsStr, OsString};
+use std::fs::{self, DirBuilder, File};
+use std::hash::{Hash, Hasher};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Output;
 
 use anyhow::anyhow;
-use cc::Build;
-use target_lexicon::Triple;
+use cc::{Build, Tool};
+use log::info;
+use target_lexicon::{OperatingSystem, Triple};
 
+use super::args::Emit;
 use super::Arguments;
 
-/// Emit some C code and compile it. Returns the path of the executable.
-pub fn compile(args: &Arguments, target: &Triple, code: String) -> anyhow::Result<PathBuf> {
+/// Emit some C code and compile it into an executable named after `path`, using
+/// the compiler settings from the manifest if there is one. The C compiler is
+/// skipped if the executable was already compiled from the same code with the
+/// same settings. Returns the path of the executable.
+pub fn compile(
+    args: &Arguments,
+    target: &Triple,
+    path: &Path,
+    code: String,
+) -> anyhow::Result<PathBuf> {
     let project_dir = env::current_dir()?;
-    let artifacts = args.options().artifacts.as_path();
-
-    DirBuilder::new().recursive(true).create(artifacts)?;
-
-    let path = &args.options().path;
-    let code_path = artifacts.join(path.with_extension("c"));
-
-    {
-        let mut file = File::create(&code_path)?;
-        file.write_all(code.as_bytes())?;
-    }
+    let artifacts = args.options().artifacts();
 
     let mut exec_name = path.with_extension("");
 
-    let mut build = Build::new();
-    build
-        .opt_level(0)
-        .target(&target.to_string())
-        .host(&Triple::host().to_string())
-        .cargo_metadata(false)
-        .warnings(false);
-
-    let tool = build.get_compiler();
+    let tool = build(args, target).get_compiler();
 
     let args: Vec<&OsStr> = if tool.is_like_clang() || tool.is_like_gnu() {
         vec![&OsStr::new("-o"), exec_name.as_os_str()]
@@ -46,6 +41,18 @@ pub fn compile(args: &Arguments, target: &Triple, code: String) -> anyhow::Resul
         return Err(anyhow!("unsupported compiler {tool:?}"));
     };
 
+    let exec_path = project_dir.join(artifacts).join(&exec_name);
+    let code_dir = project_dir
+        .join(artifacts)
+        .join(path.parent().unwrap_or(Path::new("")));
+    let inputs = input_files(&code, &tool, &project_dir.join(artifacts), &code_dir);
+    let hash = build_hash(&code, &tool, &args, target, &exec_path, &inputs);
+    if up_to_date(artifacts, path, &hash, &exec_path) {
+        return Ok(exec_path);
+    }
+
+    let code_path = write_code(artifacts, path, "c", &code)?;
+
     let output = tool
         .to_command()
         .current_dir(artifacts)
@@ -53,19 +60,278 @@ pub fn compile(args: &Arguments, target: &Triple, code: String) -> anyhow::Resul
         .args(args)
         .output()?;
 
-    let exec_name = exec_name.to_string_lossy();
-
     if output.status.success() {
-        let path = project_dir.join(artifacts).join(exec_name.as_ref());
-        Ok(path)
+        write_code(artifacts, path, "hash", &hash)?;
+        Ok(exec_path)
     } else {
-        let output = if output.stderr.is_empty() {
-            String::from_utf8_lossy(&output.stdout)
+        Err(compiler_error(output))
+    }
+}
+
+/// Emit some C code along with its header and compile it into an object file
+/// or library named after `path`, depending on `emit`. The header is put next
+/// to the compiled library. Just like with [`compile`], the C compiler is
+/// skipped if nothing changed. Returns the path of the object file or library.
+pub fn compile_library(
+    args: &Arguments,
+    target: &Triple,
+    path: &Path,
+    emit: Emit,
+    (code, header): (String, String),
+) -> anyhow::Result<PathBuf> {
+    let project_dir = env::current_dir()?;
+    let artifacts = args.options().artifacts();
+    write_code(artifacts, path, "h", &header)?;
+
+    let name = library_name(path);
+    let dir = project_dir
+        .join(artifacts)
+        .join(path.parent().unwrap_or(Path::new("")));
+
+    let mut build = build(args, target);
+
+    if let Emit::Staticlib = emit {
+        let tool = build.get_compiler();
+        let file = if tool.is_like_msvc() {
+            format!("{name}.lib")
         } else {
-            String::from_utf8_lossy(&output.stderr)
+            format!("lib{name}.a")
+        };
+
+        let lib_path = dir.join(file);
+        let inputs = input_files(&code, &tool, &project_dir, &dir);
+        let hash = build_hash(&code, &tool, &[] as &[&OsStr], target, &lib_path, &inputs);
+        if up_to_date(artifacts, path, &hash, &lib_path) {
+            return Ok(lib_path);
+        }
+
+        let code_path = write_code(artifacts, path, "c", &code)?;
+        build
+            .file(project_dir.join(&code_path))
+            .out_dir(&dir)
+            .try_compile(&name)
+            .map_err(|error| anyhow!("compiler unsuccessful: {error}"))?;
+
+        write_code(artifacts, path, "hash", &hash)?;
+        return Ok(lib_path);
+    }
+
+    let tool = build.get_compiler();
+    let windows = target.operating_system == OperatingSystem::Windows;
+
+    let (file, flags): (_, Vec<OsString>) = if tool.is_like_clang() || tool.is_like_gnu() {
+        let (file, flags) = match emit {
+            Emit::Obj => (format!("{name}.o"), vec!["-c"]),
+            _ if windows => (format!("{name}.dll"), vec!["-shared"]),
+            _ if target.operating_system == OperatingSystem::Darwin => {
+                (format!("lib{name}.dylib"), vec!["-shared", "-fPIC"])
+            }
+
+            _ => (format!("lib{name}.so"), vec!["-shared", "-fPIC"]),
+        };
+
+        let mut flags: Vec<_> = flags.into_iter().map(OsString::from).collect();
+        flags.extend(["-o".into(), file.clone().into()]);
+        (file, flags)
+    } else if tool.is_like_msvc() {
+        match emit {
+            Emit::Obj => {
+                let file = format!("{name}.obj");
+                (file.clone(), vec!["/c".into(), format!("/Fo{file}").into()])
+            }
+
+            _ => {
+                let file = format!("{name}.dll");
+                (
+                    file.clone(),
+                    vec!["/LD".into(), format!("/Fe{file}").into()],
+                )
+            }
         }
-        .to_string();
+    } else {
+        return Err(anyhow!("unsupported compiler {tool:?}"));
+    };
 
-        Err(anyhow::anyhow!("compiler unsuccessful. output:\n{output}"))
+    let lib_path = dir.join(file);
+    let inputs = input_files(&code, &tool, &dir, &dir);
+    let hash = build_hash(&code, &tool, &flags, target, &lib_path, &inputs);
+    if up_to_date(artifacts, path, &hash, &lib_path) {
+        return Ok(lib_path);
     }
+
+    let code_path = write_code(artifacts, path, "c", &code)?;
+
+    let output = tool
+        .to_command()
+        .current_dir(&dir)
+        .arg(project_dir.join(&code_path))
+        .args(flags)
+        .output()?;
+
+    if output.status.success() {
+        write_code(artifacts, path, "hash", &hash)?;
+        Ok(lib_path)
+    } else {
+        Err(compiler_error(output))
+    }
+}
+
+/// Set up a build for the given target, using the compiler settings from the
+/// manifest if there is one.
+fn build(args: &Arguments, target: &Triple) -> Build {
+    let mut build = Build::new();
+    build
+        .opt_level_str(args.options().opt_level.as_str())
+        .debug(args.options().debug)
+        .target(&target.to_string())
+        .host(&Triple::host().to_string())
+        .cargo_metadata(false)
+        .warnings(false);
+
+    if let Some(manifest) = &args.options().manifest {
+        if let Some(compiler) = &manifest.c.compiler {
+            build.compiler(compiler);
+        }
+
+        for flag in manifest.c.flags.iter() {
+            build.flag(flag);
+        }
+    }
+
+    build
+}
+
+/// Write some generated code named after `path` with the given extension to
+/// the artifacts directory, returning the path of the written file.
+fn write_code(
+    artifacts: &Path,
+    path: &Path,
+    extension: &str,
+    code: &str,
+) -> anyhow::Result<PathBuf> {
+    let code_path = artifacts.join(path.with_extension(extension));
+
+    DirBuilder::new()
+        .recursive(true)
+        .create(code_path.parent().unwrap_or(artifacts))?;
+
+    let mut file = File::create(&code_path)?;
+    file.write_all(code.as_bytes())?;
+
+    Ok(code_path)
+}
+
+/// Find the files besides the generated code which the compiler reads, so that
+/// changing them forces a rebuild. These are any files named by the compiler
+/// flags, such as C files implementing external functions, and the headers
+/// included for external functions, looked for in `code_dir`, where the
+/// generated code is, and in the `-I` directories. Relative paths are taken
+/// from `dir`, where the compiler is run.
+fn input_files(code: &str, tool: &Tool, dir: &Path, code_dir: &Path) -> Vec<PathBuf> {
+    let mut include_dirs = vec![code_dir.to_path_buf()];
+    let mut files = Vec::new();
+
+    let mut flags = tool.args().iter();
+    while let Some(flag) = flags.next() {
+        if dir.join(flag).is_file() {
+            files.push(dir.join(flag));
+            continue;
+        }
+
+        let Some(flag) = flag.to_str() else {
+            continue;
+        };
+
+        if flag == "-I" {
+            include_dirs.extend(flags.next().map(|include| dir.join(include)));
+        } else if let Some(include) = flag.strip_prefix("-I").or_else(|| flag.strip_prefix("/I")) {
+            include_dirs.push(dir.join(include));
+        }
+    }
+
+    for line in code.lines() {
+        let Some(header) = line
+            .strip_prefix("#include \"")
+            .and_then(|line| line.strip_suffix('"'))
+        else {
+            continue;
+        };
+
+        let header = include_dirs
+            .iter()
+            .map(|include| include.join(header))
+            .find(|header| header.is_file());
+
+        files.extend(header);
+    }
+
+    files
+}
+
+/// Hash everything that goes into compiling some generated code: the code
+/// itself, the compiler along with its flags and environment, the target,
+/// where the output goes and the contents of the other files the compiler
+/// reads. The hash is stored next to the generated code.
+///
+/// A different hash only means compiling again, so it doesn't matter that the
+/// hasher may change between versions of Rust.
+fn build_hash(
+    code: &str,
+    tool: &Tool,
+    flags: &[impl AsRef<OsStr>],
+    target: &Triple,
+    output: &Path,
+    inputs: &[PathBuf],
+) -> String {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    code.hash(&mut hasher);
+    tool.path().hash(&mut hasher);
+    tool.args().hash(&mut hasher);
+    tool.env().hash(&mut hasher);
+
+    for flag in flags {
+        flag.as_ref().hash(&mut hasher);
+    }
+
+    target.to_string().hash(&mut hasher);
+    output.hash(&mut hasher);
+
+    for input in inputs {
+        input.hash(&mut hasher);
+        fs::read(input).ok().hash(&mut hasher);
+    }
+
+    format!("{:016x}\n", hasher.finish())
+}
+
+/// Check if `output` has already been compiled from code named after `path`
+/// with the same hash, in which case it can be used as is.
+fn up_to_date(artifacts: &Path, path: &Path, hash: &str, output: &Path) -> bool {
+    let stored = fs::read_to_string(artifacts.join(path.with_extension("hash")));
+    if output.exists() && matches!(stored, Ok(stored) if stored == hash) {
+        info!("'{}' is up to date", output.display());
+        true
+    } else {
+        false
+    }
+}
+
+/// Get the name of a library built from the given path, which is also used
+/// to name the types in its header.
+pub fn library_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn compiler_error(output: Output) -> anyhow::Error {
+    let output = if output.stderr.is_empty() {
+        String::from_utf8_lossy(&output.stdout)
+    } else {
+        String::from_utf8_lossy(&output.stderr)
+    }
+    .to_string();
+
+    anyhow::anyhow!("compiler unsuccessful. output:\n{output}")
 }
@@ -1,53 +1,206 @@
+use std::collections::HashMap;
 use std::env;
 use std::fs::{DirBuilder, File};
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use codespan_reporting::diagnostic as cr;
-use codespan_reporting::files::SimpleFiles;
+use codespan_reporting::files::{Files, SimpleFiles};
 use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
 use codespan_reporting::term::{self, Config, DisplayStyle};
 use console::{style, Term};
 
-use zippy_common::message::{self, Messages};
-use zippy_common::{Driver, EvalAmount, IrOutput};
+use zippy_common::message::{self, Messages, SourceMap};
+use zippy_common::sizes::DataLayout;
+use zippy_common::{Driver, EvalAmount, IrOutput, Pass, Stat};
 
-use super::args::Arguments;
+use super::args::{Arguments, Color, DiagnosticStyle, IrStage, Options};
+use super::input::read_file;
+use super::message_format::{self as format, MessageFormat};
+use super::target::get_target;
 
 pub struct ConsoleDriver {
     files: SimpleFiles<String, String>,
+    paths: HashMap<PathBuf, message::File>,
     writer: StandardStream,
     term: Term,
     config: Config,
+    format: MessageFormat,
+
+    /// Messages waiting to be written as a single SARIF log once the driver
+    /// is dropped.
+    sarif: Vec<message::Diagnostic>,
 
     preserve_output: bool,
-    ir_output: bool,
+
+    /// The intermediate representations to write out, and whether each one
+    /// goes to stdout rather than a file.
+    ir_output: HashMap<IrStage, bool>,
+
+    time_passes: bool,
+    debug_info: bool,
     partial_eval: EvalAmount,
 
     artifacts: PathBuf,
+    entry: Option<String>,
+    layout: DataLayout,
+
+    /// Directories to look for imported files in, if they can't be found next
+    /// to the importing file.
+    roots: Vec<PathBuf>,
+
+    errors: usize,
+
+    /// How many errors to show messages for, and how many messages were
+    /// hidden after reaching that limit.
+    error_limit: Option<usize>,
+    hidden: usize,
 }
 
 impl ConsoleDriver {
-    pub fn new(args: &Arguments, files: SimpleFiles<String, String>) -> Self {
-        let opts = args.options();
+    pub fn new(args: &Arguments) -> Self {
+        // Commands like `repl` don't take any options, so just use the defaults
+        let default = Options::default();
+        let opts = args.command.options().unwrap_or(&default);
+        let manifest = opts.manifest.as_ref();
+
+        // `ColorChoice::Auto` only looks at the environment, not at whether
+        // stderr is actually a terminal.
+        let color = match opts.color {
+            Color::Auto if console::user_attended_stderr() => ColorChoice::Auto,
+            Color::Auto => ColorChoice::Never,
+            Color::Always => ColorChoice::Always,
+            Color::Never => ColorChoice::Never,
+        };
+
+        match opts.color {
+            Color::Auto => {}
+            Color::Always => console::set_colors_enabled_stderr(true),
+            Color::Never => console::set_colors_enabled_stderr(false),
+        }
+
+        let mut ir_output: HashMap<_, _> = opts.ir_stages().collect();
+        if env::var("COR_OUTPUT_IR").is_ok() || opts.output_ir {
+            ir_output.entry(IrStage::Mir).or_insert(false);
+        }
+
         Self {
-            files,
-            writer: StandardStream::stderr(ColorChoice::Auto),
+            files: SimpleFiles::new(),
+            paths: HashMap::new(),
+            writer: StandardStream::stderr(color),
             term: Term::stderr(),
             config: Config {
-                display_style: DisplayStyle::Rich,
+                display_style: match opts.diagnostic_style {
+                    Some(DiagnosticStyle::Rich) => DisplayStyle::Rich,
+                    Some(DiagnosticStyle::Medium) => DisplayStyle::Medium,
+                    Some(DiagnosticStyle::Short) => DisplayStyle::Short,
+                    None if opts.message_format == MessageFormat::Short => DisplayStyle::Short,
+                    None => DisplayStyle::Rich,
+                },
                 ..Default::default()
             },
+            format: opts.message_format,
+            sarif: Vec::new(),
 
             preserve_output: env::var("COR_PRESERVE_OUTPUT").is_ok() || opts.preserve_output,
-            ir_output: env::var("COR_OUTPUT_IR").is_ok() || opts.output_ir,
+            ir_output,
+            time_passes: opts.time_passes,
+            debug_info: opts.debug,
             partial_eval: if env::var("COR_NO_EVAL").is_ok() || opts.no_eval {
                 EvalAmount::None
             } else {
-                EvalAmount::Full
+                manifest
+                    .map(|manifest| manifest.build.eval.into())
+                    .unwrap_or(EvalAmount::Full)
             },
 
-            artifacts: args.options().artifacts.clone(),
+            artifacts: opts.artifacts().to_path_buf(),
+
+            // Tests are run by a generated harness and expressions in the repl
+            // are evaluated on their own, so neither need an entry point
+            entry: args.command.needs_entry().then(|| {
+                manifest
+                    .map(|manifest| manifest.package.entry.clone())
+                    .unwrap_or_else(|| "main".into())
+            }),
+
+            layout: DataLayout::from_triple(&get_target(args)),
+            roots: manifest
+                .map(|manifest| manifest.package.roots.clone())
+                .unwrap_or_default(),
+
+            errors: 0,
+            error_limit: opts.error_limit,
+            hidden: 0,
+        }
+    }
+
+    /// Add a source file to be used when reporting messages, returning its id.
+    pub fn add_file(&mut self, path: &Path, source: String) -> message::File {
+        let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let file = self.files.add(path.to_string_lossy().into(), source);
+        self.paths.insert(key, file);
+        file
+    }
+
+    /// Replace the source text of a file which has already been added.
+    pub fn set_source(&mut self, file: message::File, source: String) {
+        // `SimpleFiles` can't be modified, but files are numbered in the
+        // order they are added, so adding them all again keeps their ids.
+        let mut files = SimpleFiles::new();
+        let mut id = 0;
+
+        while let Ok(old) = self.files.get(id) {
+            let text = if id == file {
+                source.clone()
+            } else {
+                old.source().clone()
+            };
+
+            files.add(old.name().clone(), text);
+            id += 1;
+        }
+
+        self.files = files;
+    }
+
+    /// Get the name of a file, along with the line and column of an offset
+    /// into it. Lines and columns start at one.
+    pub fn location(&self, file: message::File, offset: usize) -> Option<(&str, usize, usize)> {
+        let name = self.files.get(file).ok()?.name();
+        let location = self.files.location(file, offset).ok()?;
+        Some((name, location.line_number, location.column_number))
+    }
+
+    /// Get the path and id of every file added so far.
+    pub fn paths(&self) -> impl Iterator<Item = (&Path, message::File)> {
+        self.paths
+            .iter()
+            .map(|(path, file)| (path.as_path(), *file))
+    }
+
+    /// Forget about any errors reported so far.
+    pub fn clear_errors(&mut self) {
+        self.summarize_hidden();
+        self.errors = 0;
+    }
+
+    /// Returns `true` if any errors have been reported so far.
+    pub fn has_errors(&self) -> bool {
+        self.errors > 0
+    }
+
+    /// Mention how many messages weren't shown because of the error limit.
+    fn summarize_hidden(&mut self) {
+        if self.hidden > 0 {
+            let (count, plural) = (self.hidden, if self.hidden == 1 { "" } else { "s" });
+            eprintln!(
+                "{}: {count} more message{plural} not shown because of --error-limit",
+                style("note").green()
+            );
+
+            self.hidden = 0;
         }
     }
 
@@ -64,20 +217,92 @@ impl ConsoleDriver {
             .recursive(true)
             .create(self.artifacts.as_path())?;
 
+        let stem = |file: message::File| {
+            self.files
+                .get(file)
+                .ok()
+                .and_then(|file| Path::new(file.name()).file_stem())
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| format!("file{file}"))
+        };
+
         let name = match at {
-            IrOutput::Mir(name) => self.artifacts.join(format!("{name}.mir.z")),
+            IrOutput::Tokens(file) => format!("{}.tokens.txt", stem(file)),
+            IrOutput::Cst(file) => format!("{}.cst.txt", stem(file)),
+            IrOutput::Hir(file) => format!("{}.hir.txt", stem(file)),
+            IrOutput::ResolvedHir => "resolved.hir.txt".into(),
+            IrOutput::DepsDot => "dependencies.dot".into(),
+            IrOutput::Kinds => "kinds.txt".into(),
+            IrOutput::Thir => "thir.txt".into(),
+            IrOutput::Mir(name) => format!("{name}.mir.z"),
+            IrOutput::MirDot(name) => format!("{name}.mir.dot"),
+            IrOutput::C => "emitted.c".into(),
         };
 
-        let mut file = File::create(name)?;
+        let mut file = File::create(self.artifacts.join(name))?;
         file.write_all(data.as_bytes())?;
 
         Ok(())
     }
 }
 
+impl Drop for ConsoleDriver {
+    fn drop(&mut self) {
+        self.summarize_hidden();
+
+        if self.format == MessageFormat::Sarif {
+            println!("{:#}", format::sarif(&self.files, &self.sarif));
+        }
+    }
+}
+
 impl Driver for ConsoleDriver {
-    fn report(&mut self, messages: Messages) {
+    fn report(&mut self, mut messages: Messages) {
+        // Messages are accumulated in whatever order the passes ran in, so
+        // sort them by where they occur instead.
+        messages.msgs.sort_by_key(|msg| {
+            let span = msg
+                .labels
+                .iter()
+                .find(|label| matches!(label.style, message::LabelStyle::Primary))
+                .or_else(|| msg.labels.first())
+                .map(|label| (label.span.file, label.span.start, label.span.end));
+
+            (span.is_none(), span)
+        });
+
         for msg in messages.msgs {
+            let error = matches!(
+                msg.severity,
+                message::Severity::Bug | message::Severity::Error
+            );
+
+            if error {
+                self.errors += 1;
+            }
+
+            match self.format {
+                MessageFormat::Human | MessageFormat::Short => {}
+                MessageFormat::Json => {
+                    println!("{}", format::json(&self.files, &msg));
+                    continue;
+                }
+
+                MessageFormat::Sarif => {
+                    self.sarif.push(msg);
+                    continue;
+                }
+            }
+
+            // Once the limit is reached, only the errors themselves are still
+            // counted.
+            if let Some(limit) = self.error_limit {
+                if self.errors > limit || (self.errors == limit && !error) {
+                    self.hidden += 1;
+                    continue;
+                }
+            }
+
             let severity = match msg.severity {
                 message::Severity::Bug => cr::Severity::Bug,
                 message::Severity::Error => cr::Severity::Error,
@@ -99,12 +324,17 @@ impl Driver for ConsoleDriver {
                 })
                 .collect();
 
+            let mut notes = msg.notes;
+            if let Some(explanation) = msg.code.as_deref().and_then(message::explain) {
+                notes.push(format!("help: run 'zc explain {}'", explanation.code));
+            }
+
             let msg = cr::Diagnostic {
                 severity,
                 code: msg.code,
                 message: msg.message,
                 labels,
-                notes: msg.notes,
+                notes,
             };
 
             term::emit(&mut self.writer, &self.config, &self.files, &msg).unwrap();
@@ -125,18 +355,101 @@ impl Driver for ConsoleDriver {
     }
 
     fn output_ir(&mut self, at: IrOutput, data: impl FnOnce() -> String) {
-        if self.ir_output {
-            if let Err(e) = self.write_ir_file(at, data()) {
-                eprintln!("error writing ir file: {e}");
+        let stage = match at {
+            IrOutput::Tokens(_) => IrStage::Tokens,
+            IrOutput::Cst(_) => IrStage::Cst,
+            IrOutput::Hir(_) => IrStage::Hir,
+            IrOutput::ResolvedHir => IrStage::Resolved,
+            IrOutput::DepsDot => IrStage::DepsDot,
+            IrOutput::Kinds => IrStage::Kinds,
+            IrOutput::Thir => IrStage::Thir,
+            IrOutput::Mir(_) => IrStage::Mir,
+            IrOutput::MirDot(_) => IrStage::MirDot,
+            IrOutput::C => IrStage::C,
+        };
+
+        match self.ir_output.get(&stage) {
+            Some(true) => print!("{}", data()),
+            Some(false) => {
+                if let Err(e) = self.write_ir_file(at, data()) {
+                    eprintln!("error writing ir file: {e}");
+                }
             }
+
+            None => {}
         }
     }
 
+    fn load_import(&mut self, from: message::File, name: &str) -> Option<(message::File, String)> {
+        let from = self.files.get(from).ok()?.name();
+        let dir = Path::new(from).parent().unwrap_or_else(|| Path::new(""));
+        let name = format!("{name}.z");
+
+        let path = std::iter::once(dir)
+            .chain(self.roots.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(&name))
+            .find(|path| path.exists())?;
+
+        if let Some(file) = path
+            .canonicalize()
+            .ok()
+            .and_then(|key| self.paths.get(&key))
+        {
+            let source = self.files.get(*file).ok()?.source().clone();
+            return Some((*file, source));
+        }
+
+        let source = read_file(&path).ok()?;
+        let file = self.add_file(&path, source.clone());
+        Some((file, source))
+    }
+
     fn entry_name(&mut self) -> Option<String> {
-        Some("main".into())
+        self.entry.clone()
     }
 
     fn eval_amount(&mut self) -> EvalAmount {
         self.partial_eval
     }
+
+    fn source_map(&mut self) -> Option<SourceMap> {
+        if !self.debug_info {
+            return None;
+        }
+
+        let mut map = SourceMap::new();
+        let mut id = 0;
+
+        // The C compiler runs in the artifacts directory, so relative paths
+        // would point at the wrong files.
+        while let Ok(file) = self.files.get(id) {
+            let name = Path::new(file.name())
+                .canonicalize()
+                .map(|path| path.to_string_lossy().into_owned())
+                .unwrap_or_else(|_| file.name().clone());
+
+            map.add(name, file.source());
+            id += 1;
+        }
+
+        Some(map)
+    }
+
+    fn data_layout(&mut self) -> DataLayout {
+        self.layout
+    }
+
+    fn report_pass(&mut self, pass: Pass, time: Duration) {
+        if self.time_passes {
+            self.clear_line().unwrap();
+            eprintln!("{}: {time:>10.2?}  {}", style("time").cyan(), pass.name());
+        }
+    }
+
+    fn report_stat(&mut self, stat: Stat, count: usize) {
+        if self.time_passes {
+            self.clear_line().unwrap();
+            eprintln!("{}: {count:>10}  {}", style("size").cyan(), stat.name());
+        }
+    }
 }
@@ -0,0 +1,130 @@
+//! Generating documentation for a program. Every file of the program gets a
+//! static HTML page listing its top-level definitions, along with their
+//! inferred types, the `---` comments written before them and where they are
+//! defined.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+
+use zippy_common::Driver;
+use zippy_frontend::ide::{self, Documented, SymbolKind};
+use zippy_frontend::{check, collect_files, Database, Project, SourceProgram};
+
+use super::args::{Arguments, Options};
+use super::console_driver::ConsoleDriver;
+
+pub fn document(args: &Arguments, opts: &Options) -> anyhow::Result<()> {
+    let source = opts.source();
+    let src = source.read()?;
+
+    let mut driver = ConsoleDriver::new(args);
+    let root = driver.add_file(source.path(), src.clone());
+
+    let db = Database::default();
+    let program = SourceProgram::new(&db, src, root);
+    let mut programs = HashMap::from([(root, program)]);
+
+    let (files, imports, _) = collect_files(&db, program, |from, name| {
+        let (id, text) = driver.load_import(from, name)?;
+        let program = programs
+            .entry(id)
+            .or_insert_with(|| SourceProgram::new(&db, text, id));
+        Some(*program)
+    });
+
+    let project = Project::new(&db, files, imports);
+    let _ = check(&db, &mut driver, project, root);
+
+    if driver.has_errors() {
+        return Err(anyhow!("cannot document a program with errors"));
+    }
+
+    let dir = opts.artifacts().join("doc");
+    fs::create_dir_all(&dir)?;
+
+    for program in project.files(&db) {
+        let file = program.id(&db);
+        let Some((name, _, _)) = driver.location(file, 0) else {
+            continue;
+        };
+
+        // The root file may not have a path of its own if it was read from
+        // stdin or given on the command line.
+        let path = if file == root {
+            opts.output()
+        } else {
+            PathBuf::from(name)
+        };
+
+        let stem = path.file_stem().unwrap_or(path.as_os_str());
+        let page = dir.join(Path::new(stem).with_extension("html"));
+
+        let defs = ide::documentation(&db, project, file);
+        fs::write(&page, render(&driver, name, &defs))?;
+        eprintln!("documented '{name}' in '{}'", page.display());
+    }
+
+    Ok(())
+}
+
+/// Render the page for a single file.
+fn render(driver: &ConsoleDriver, name: &str, defs: &[Documented]) -> String {
+    let name = escape(name);
+    let mut res = String::new();
+
+    writeln!(res, "<!DOCTYPE html>").unwrap();
+    writeln!(res, "<html>").unwrap();
+    writeln!(res, "<head>").unwrap();
+    writeln!(res, "<meta charset=\"utf-8\">").unwrap();
+    writeln!(res, "<title>{name}</title>").unwrap();
+    writeln!(res, "<style>{STYLE}</style>").unwrap();
+    writeln!(res, "</head>").unwrap();
+    writeln!(res, "<body>").unwrap();
+    writeln!(res, "<h1>{name}</h1>").unwrap();
+
+    if defs.is_empty() {
+        writeln!(res, "<p>This file has no definitions.</p>").unwrap();
+    }
+
+    for def in defs {
+        let signature = match def.kind {
+            SymbolKind::Type => format!("type {} :: {}", def.name, def.ty),
+            _ => format!("{} : {}", def.name, def.ty),
+        };
+
+        writeln!(res, "<section id=\"{}\">", escape(&def.name)).unwrap();
+        writeln!(res, "<h2><code>{}</code></h2>", escape(&signature)).unwrap();
+
+        // Paragraphs are separated by blank lines, just like in Markdown.
+        let doc = def.doc.as_deref().unwrap_or_default();
+        for paragraph in doc.split("\n\n").filter(|text| !text.trim().is_empty()) {
+            writeln!(res, "<p>{}</p>", escape(paragraph.trim())).unwrap();
+        }
+
+        if let Some((path, line, column)) = driver.location(def.span.file, def.span.start) {
+            let location = format!("{path}:{line}:{column}");
+            writeln!(res, "<p class=\"location\">{}</p>", escape(&location)).unwrap();
+        }
+
+        writeln!(res, "</section>").unwrap();
+    }
+
+    writeln!(res, "</body>").unwrap();
+    writeln!(res, "</html>").unwrap();
+    res
+}
+
+const STYLE: &str = "body { max-width: 50em; margin: auto; font-family: sans-serif; } \
+    section { border-top: 1px solid #ddd; } \
+    .location { color: #777; font-size: small; }";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
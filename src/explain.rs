@@ -0,0 +1,17 @@
+//! Showing the long-form explanations of message codes.
+
+use anyhow::anyhow;
+
+use zippy_common::message::explain;
+
+use super::args::ExplainOptions;
+
+pub fn explain_code(opts: &ExplainOptions) -> anyhow::Result<()> {
+    let explanation =
+        explain(&opts.code).ok_or_else(|| anyhow!("no explanation for '{}'", opts.code))?;
+
+    println!("{}", explanation.code);
+    println!();
+    print!("{}", explanation.text);
+    Ok(())
+}
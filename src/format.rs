@@ -0,0 +1,39 @@
+//! Formatting source files in place, or checking that they are formatted.
+
+use std::fs;
+
+use anyhow::anyhow;
+
+use zippy_common::Driver;
+use zippy_frontend::fmt::format;
+
+use super::args::{Arguments, FormatOptions};
+use super::console_driver::ConsoleDriver;
+use super::input::read_file;
+
+pub fn format_file(args: &Arguments, opts: &FormatOptions) -> anyhow::Result<()> {
+    let path = &opts.path;
+    let source = read_file(path)?;
+
+    let mut driver = ConsoleDriver::new(args);
+    let file = driver.add_file(path, source.clone());
+
+    let formatted = match format(&source, file) {
+        Ok(formatted) => formatted,
+        Err(messages) => {
+            driver.report(messages);
+            return Err(anyhow!("cannot format code with syntax errors"));
+        }
+    };
+
+    if formatted == source {
+        return Ok(());
+    }
+
+    if opts.check {
+        return Err(anyhow!("'{}' is not formatted", path.display()));
+    }
+
+    fs::write(path, formatted)?;
+    Ok(())
+}
@@ -1,7 +1,44 @@
 use std::fs::File;
-use std::io::Read;
+use std::io::{self, Read};
 use std::path::Path;
 
+/// Where the source code of the root file of a program comes from.
+#[derive(Clone, Copy, Debug)]
+pub enum Source<'a> {
+    File(&'a Path),
+
+    /// Standard input, given as the path `-`.
+    Stdin,
+
+    /// Source code given on the command line with `-e`.
+    Expr(&'a str),
+}
+
+impl Source<'_> {
+    pub fn read(&self) -> anyhow::Result<String> {
+        match self {
+            Self::File(path) => read_file(path),
+            Self::Stdin => {
+                let mut buf = String::new();
+                io::stdin().read_to_string(&mut buf)?;
+                Ok(buf)
+            }
+
+            Self::Expr(source) => Ok(source.to_string()),
+        }
+    }
+
+    /// The path to show in messages. Imports from source code which isn't in
+    /// a file are looked for in the current directory.
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::File(path) => path,
+            Self::Stdin => Path::new("<stdin>"),
+            Self::Expr(_) => Path::new("<expr>"),
+        }
+    }
+}
+
 pub fn read_file(path: &Path) -> anyhow::Result<String> {
     let mut buf = Vec::new();
     File::open(path)?.read_to_end(&mut buf)?;
@@ -0,0 +1,93 @@
+//! Conversions between the compiler's spans and diagnostics and those used by
+//! the language server protocol. Positions in the protocol are given as a line
+//! and a column counted in UTF-16 code units, while spans are byte offsets.
+
+use lsp_types::{
+    DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString, Position, Range,
+    Url,
+};
+
+use zippy_common::message::{self, Diagnostic, LabelStyle, Span};
+
+/// Get the position of the given byte offset in the text.
+pub fn position(text: &str, offset: usize) -> Position {
+    let offset = offset.min(text.len());
+    let before = &text[..offset];
+
+    let line = before.matches('\n').count();
+    let start = before.rfind('\n').map(|at| at + 1).unwrap_or(0);
+    let character = before[start..].encode_utf16().count();
+
+    Position::new(line as u32, character as u32)
+}
+
+/// Get the byte offset of the given position in the text. Positions past the
+/// end of a line are clamped to the end of that line.
+pub fn offset(text: &str, position: Position) -> usize {
+    let mut start = 0;
+    for _ in 0..position.line {
+        match text[start..].find('\n') {
+            Some(at) => start += at + 1,
+            None => return text.len(),
+        }
+    }
+
+    let mut units = 0;
+    for (at, ch) in text[start..].char_indices() {
+        if ch == '\n' || units >= position.character as usize {
+            return start + at;
+        }
+
+        units += ch.len_utf16();
+    }
+
+    text.len()
+}
+
+pub fn range(text: &str, span: Span) -> Range {
+    Range::new(position(text, span.start), position(text, span.end))
+}
+
+/// Convert a diagnostic produced by the compiler into one suitable for the
+/// given document.
+pub fn diagnostic(uri: &Url, text: &str, diagnostic: Diagnostic) -> lsp_types::Diagnostic {
+    let severity = match diagnostic.severity {
+        message::Severity::Bug | message::Severity::Error => DiagnosticSeverity::ERROR,
+        message::Severity::Warning => DiagnosticSeverity::WARNING,
+        message::Severity::Note => DiagnosticSeverity::INFORMATION,
+        message::Severity::Help => DiagnosticSeverity::HINT,
+    };
+
+    let primary = diagnostic
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary)
+        .map(|label| range(text, label.span))
+        .unwrap_or_default();
+
+    let related: Vec<_> = diagnostic
+        .labels
+        .iter()
+        .filter(|label| label.style == LabelStyle::Secondary)
+        .map(|label| DiagnosticRelatedInformation {
+            location: Location::new(uri.clone(), range(text, label.span)),
+            message: label.message.clone(),
+        })
+        .collect();
+
+    let mut message = diagnostic.message;
+    for note in diagnostic.notes {
+        message.push('\n');
+        message.push_str(&note);
+    }
+
+    lsp_types::Diagnostic {
+        range: primary,
+        severity: Some(severity),
+        code: diagnostic.code.map(NumberOrString::String),
+        source: Some("zc".into()),
+        message,
+        related_information: (!related.is_empty()).then_some(related),
+        ..Default::default()
+    }
+}
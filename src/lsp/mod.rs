@@ -0,0 +1,411 @@
+//! A language server speaking the language server protocol over stdio. The
+//! server keeps a single salsa database alive for as long as it runs, so that
+//! an edit only recomputes the queries it actually affects.
+//!
+//! Every open document is treated as the root of its own project, and any
+//! files it imports are read from disk unless they are open in the editor.
+
+mod convert;
+
+use std::collections::HashMap;
+use std::fs;
+
+use log::{debug, info};
+use lsp_server::{
+    Connection, ErrorCode, ExtractError, Message, Notification, Request, RequestId, Response,
+};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{DocumentSymbolRequest, GotoDefinition, HoverRequest, References};
+use lsp_types::{
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams,
+    GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability, Location,
+    MarkupContent, MarkupKind, OneOf, Position, PublishDiagnosticsParams, ReferenceParams,
+    ServerCapabilities, SymbolKind, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use serde::Serialize;
+use zippy_common::message::{Diagnostic, File, LabelStyle, Span};
+use zippy_frontend::{collect_files, ide, Database, Project, SourceProgram};
+
+pub fn run() -> anyhow::Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = serde_json::to_value(ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    })?;
+
+    connection.initialize(capabilities)?;
+    info!("language server initialized");
+
+    let mut server = Server {
+        connection,
+        db: Database::default(),
+        documents: HashMap::new(),
+        programs: HashMap::new(),
+        uris: Vec::new(),
+    };
+
+    server.serve()?;
+    io_threads.join()?;
+
+    info!("language server shut down");
+
+    Ok(())
+}
+
+struct Server {
+    connection: Connection,
+    db: Database,
+
+    /// The project rooted at every open document, identified by its uri.
+    documents: HashMap<Url, Project>,
+
+    /// Every file known to the server, whether opened by the editor or
+    /// imported by an open document.
+    programs: HashMap<Url, SourceProgram>,
+
+    /// The uri of every known file, indexed by its id.
+    uris: Vec<Url>,
+}
+
+impl Server {
+    fn serve(&mut self) -> anyhow::Result<()> {
+        while let Ok(message) = self.connection.receiver.recv() {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        break;
+                    }
+
+                    self.handle_request(request)?;
+                }
+
+                Message::Notification(notification) => self.handle_notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_request(&self, request: Request) -> anyhow::Result<()> {
+        debug!("got request '{}'", request.method);
+
+        let Some(request) = self.dispatch::<HoverRequest>(request, Self::hover)? else {
+            return Ok(());
+        };
+
+        let Some(request) = self.dispatch::<GotoDefinition>(request, Self::definition)? else {
+            return Ok(());
+        };
+
+        let Some(request) = self.dispatch::<References>(request, Self::references)? else {
+            return Ok(());
+        };
+
+        let Some(request) = self.dispatch::<DocumentSymbolRequest>(request, Self::symbols)? else {
+            return Ok(());
+        };
+
+        let message = format!("unknown request '{}'", request.method);
+        self.respond_error(request.id, ErrorCode::MethodNotFound, message)
+    }
+
+    /// Answer the request if it is of the given kind, and give it back
+    /// otherwise. Requests with malformed parameters are answered with an
+    /// error rather than taking down the server.
+    fn dispatch<R: lsp_types::request::Request>(
+        &self,
+        request: Request,
+        handle: impl FnOnce(&Self, R::Params) -> R::Result,
+    ) -> anyhow::Result<Option<Request>> {
+        let id = request.id.clone();
+
+        match request.extract(R::METHOD) {
+            Ok((id, params)) => self.respond(id, handle(self, params))?,
+            Err(ExtractError::MethodMismatch(request)) => return Ok(Some(request)),
+            Err(ExtractError::JsonError { method, error }) => {
+                let message = format!("malformed request '{method}': {error}");
+                self.respond_error(id, ErrorCode::InvalidParams, message)?;
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let params = params.text_document_position_params;
+        let (project, file, offset) = self.locate(&params.text_document.uri, params.position)?;
+        let (span, ty) = ide::hover(&self.db, project, file, offset)?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("```zippy\n{ty}\n```"),
+            }),
+            range: Some(self.location(span)?.range),
+        })
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let params = params.text_document_position_params;
+        let (project, file, offset) = self.locate(&params.text_document.uri, params.position)?;
+
+        let span = ide::definition(&self.db, project, file, offset)?;
+        Some(GotoDefinitionResponse::Scalar(self.location(span)?))
+    }
+
+    fn references(&self, params: ReferenceParams) -> Option<Vec<Location>> {
+        let params = params.text_document_position;
+        let (project, file, offset) = self.locate(&params.text_document.uri, params.position)?;
+
+        let references = ide::references(&self.db, project, file, offset)
+            .into_iter()
+            .filter_map(|span| self.location(span))
+            .collect();
+
+        Some(references)
+    }
+
+    fn symbols(&self, params: DocumentSymbolParams) -> Option<DocumentSymbolResponse> {
+        let uri = params.text_document.uri;
+        let project = *self.documents.get(&uri)?;
+        let program = *self.programs.get(&uri)?;
+        let text = program.text(&self.db);
+
+        let symbols = ide::symbols(&self.db, project, program.id(&self.db))
+            .into_iter()
+            .map(|symbol| document_symbol(text, symbol))
+            .collect();
+
+        Some(DocumentSymbolResponse::Nested(symbols))
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> anyhow::Result<()> {
+        debug!("got notification '{}'", notification.method);
+
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+
+                let document = params.text_document;
+                self.set_text(document.uri.clone(), document.text);
+
+                let project = Project::new(&self.db, Vec::new(), HashMap::new());
+                self.documents.insert(document.uri, project);
+                self.update_projects()
+            }
+
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+
+                // We only ever ask for full document syncs, so the last change
+                // is the entire text of the document.
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.set_text(params.text_document.uri, change.text);
+                }
+
+                self.update_projects()
+            }
+
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+
+                // Other documents may import this one, so they should see what
+                // is actually on disk from now on.
+                if let Some(text) = read_uri(&uri) {
+                    self.set_text(uri.clone(), text);
+                }
+
+                self.update_projects()?;
+
+                // Clear any lingering diagnostics
+                self.notify::<PublishDiagnostics>(PublishDiagnosticsParams {
+                    uri,
+                    diagnostics: Vec::new(),
+                    version: None,
+                })
+            }
+
+            _ => Ok(()),
+        }
+    }
+
+    /// Set the text of the file with the given uri, creating it if it isn't
+    /// known already.
+    fn set_text(&mut self, uri: Url, text: String) -> SourceProgram {
+        match self.programs.get(&uri) {
+            Some(program) => {
+                program.set_text(&mut self.db).to(text);
+                *program
+            }
+
+            None => {
+                let program = SourceProgram::new(&self.db, text, self.uris.len());
+                self.uris.push(uri.clone());
+                self.programs.insert(uri, program);
+                program
+            }
+        }
+    }
+
+    /// Recollect the files of every open project, since an edit to any file may
+    /// have changed what it imports, and publish the new diagnostics.
+    fn update_projects(&mut self) -> anyhow::Result<()> {
+        let roots: Vec<_> = self.documents.keys().cloned().collect();
+
+        for uri in roots {
+            let root = self.programs[&uri];
+            let mut loaded = Vec::new();
+
+            let (files, imports, _) = collect_files(&self.db, root, |from, name| {
+                // Files loaded by this call aren't registered until it returns,
+                // but may import other files themselves.
+                let from = match self.uris.get(from) {
+                    Some(from) => from,
+                    None => &loaded[from - self.uris.len()].0,
+                };
+
+                let uri = from.join(&format!("{name}.z")).ok()?;
+                if let Some(program) = self.programs.get(&uri) {
+                    return Some(*program);
+                }
+
+                if let Some((_, program)) = loaded.iter().find(|(known, _)| *known == uri) {
+                    return Some(*program);
+                }
+
+                let text = read_uri(&uri)?;
+                let id = self.uris.len() + loaded.len();
+                let program = SourceProgram::new(&self.db, text, id);
+                loaded.push((uri, program));
+                Some(program)
+            });
+
+            for (uri, program) in loaded {
+                self.uris.push(uri.clone());
+                self.programs.insert(uri, program);
+            }
+
+            let project = self.documents[&uri];
+            project.set_files(&mut self.db).to(files);
+            project.set_imports(&mut self.db).to(imports);
+
+            self.publish_diagnostics(uri)?;
+        }
+
+        Ok(())
+    }
+
+    fn publish_diagnostics(&self, uri: Url) -> anyhow::Result<()> {
+        let (Some(project), Some(program)) = (self.documents.get(&uri), self.programs.get(&uri))
+        else {
+            return Ok(());
+        };
+
+        let file = program.id(&self.db);
+        let text = program.text(&self.db);
+
+        // Only report the diagnostics belonging to this document; those in the
+        // files it imports are shown once those files are opened.
+        let diagnostics = ide::diagnostics(&self.db, *project)
+            .into_iter()
+            .filter(|diagnostic| primary_file(diagnostic) == Some(file))
+            .map(|diagnostic| convert::diagnostic(&uri, text, diagnostic))
+            .collect();
+
+        self.notify::<PublishDiagnostics>(PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        })
+    }
+
+    /// Find the project rooted at the given document together with the id of
+    /// that document and the byte offset of the given position.
+    fn locate(&self, uri: &Url, position: Position) -> Option<(Project, File, usize)> {
+        let project = *self.documents.get(uri)?;
+        let program = *self.programs.get(uri)?;
+        let offset = convert::offset(program.text(&self.db), position);
+        Some((project, program.id(&self.db), offset))
+    }
+
+    /// Get the location of the given span in whatever file it belongs to.
+    fn location(&self, span: Span) -> Option<Location> {
+        let uri = self.uris.get(span.file)?;
+        let text = self.programs.get(uri)?.text(&self.db);
+        Some(Location::new(uri.clone(), convert::range(text, span)))
+    }
+
+    fn respond(&self, id: RequestId, result: impl Serialize) -> anyhow::Result<()> {
+        let response = Response::new_ok(id, result);
+        self.connection.sender.send(Message::Response(response))?;
+        Ok(())
+    }
+
+    fn respond_error(&self, id: RequestId, code: ErrorCode, message: String) -> anyhow::Result<()> {
+        let response = Response::new_err(id, code as i32, message);
+        self.connection.sender.send(Message::Response(response))?;
+        Ok(())
+    }
+
+    fn notify<N: lsp_types::notification::Notification>(
+        &self,
+        params: N::Params,
+    ) -> anyhow::Result<()> {
+        let notification = Notification::new(N::METHOD.into(), params);
+        self.connection
+            .sender
+            .send(Message::Notification(notification))?;
+        Ok(())
+    }
+}
+
+/// Read the contents of a file on disk.
+fn read_uri(uri: &Url) -> Option<String> {
+    let path = uri.to_file_path().ok()?;
+    fs::read_to_string(path).ok()
+}
+
+/// Get the file of the primary label of a diagnostic.
+fn primary_file(diagnostic: &Diagnostic) -> Option<File> {
+    diagnostic
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary)
+        .map(|label| label.span.file)
+}
+
+#[allow(deprecated)]
+fn document_symbol(text: &str, symbol: ide::Symbol) -> DocumentSymbol {
+    let kind = match symbol.kind {
+        ide::SymbolKind::Value => SymbolKind::CONSTANT,
+        ide::SymbolKind::Type => SymbolKind::TYPE_PARAMETER,
+        ide::SymbolKind::Test => SymbolKind::FUNCTION,
+    };
+
+    DocumentSymbol {
+        name: symbol.name,
+        detail: None,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: convert::range(text, symbol.span),
+        selection_range: convert::range(text, symbol.name_span),
+        children: None,
+    }
+}
@@ -1,58 +1,135 @@
 mod args;
 mod compile;
 mod console_driver;
+mod doc;
+mod explain;
+mod format;
 mod input;
+mod lsp;
+mod manifest;
+mod message_format;
+mod repl;
 mod target;
+mod testing;
+mod watch;
 
 use std::fs::remove_dir_all;
 use std::process::Command;
+use std::time::Instant;
 
-use zippy_backend::c::emit;
+use zippy_backend::c::{emit, emit_library};
+use zippy_common::{Driver, Pass};
 use zippy_frontend::{parse, ParseResult};
 use zippy_midend::elaborate;
 
 use anyhow::anyhow;
 use clap::Parser;
-use codespan_reporting::files::SimpleFiles;
 
 use self::args::Arguments;
-use self::compile::compile;
+use self::compile::{compile, compile_library, library_name};
 use self::console_driver::ConsoleDriver;
-use self::input::read_file;
+use self::doc::document;
+use self::explain::explain_code;
+use self::format::format_file;
+use self::repl::repl;
 use self::target::get_target;
+use self::testing::run_tests;
+use self::watch::watch;
 
 fn main() -> anyhow::Result<()> {
     env_logger::init();
 
     let mut args = Arguments::parse();
+    args.load_manifest()?;
 
-    if let self::args::Command::Clean(opts) = args.command {
-        remove_dir_all(opts.artifacts)?;
-        return Ok(());
+    match args.command {
+        self::args::Command::Clean(ref opts) => {
+            remove_dir_all(opts.artifacts())?;
+            return Ok(());
+        }
+
+        self::args::Command::Doc(ref opts) => return document(&args, opts),
+        self::args::Command::Explain(ref opts) => return explain_code(opts),
+        self::args::Command::Fmt(ref opts) => return format_file(&args, opts),
+        self::args::Command::Lsp => return lsp::run(),
+        self::args::Command::Repl => return repl(&args),
+
+        _ => {}
     }
 
     let target = get_target(&args);
-    let source = &args.options().path;
 
-    let src = read_file(source)?;
+    if let Some(opts) = args.command.watch() {
+        return watch(&args, opts, &target);
+    }
+
+    let emit_kind = args.options().emit_kind();
+    if args.command.run() && emit_kind.is_library() {
+        return Err(anyhow!("cannot run a library, only build it"));
+    }
+
+    let source = args.options().source();
 
-    let mut files = SimpleFiles::new();
-    let file = files.add(source.to_string_lossy().into(), src.clone());
+    let src = source.read()?;
 
-    let mut driver = ConsoleDriver::new(&args, files);
+    let mut driver = ConsoleDriver::new(&args);
+    let file = driver.add_file(source.path(), src.clone());
+
+    if args.command.test() {
+        return run_tests(&args, &target, &mut driver, src, file);
+    }
 
     if args.command.check() {
         let ParseResult {
             checked,
             mut names,
             entry,
+            exports,
+            ..
         } = parse(&mut driver, src, file);
 
-        let (mut types, context, decls) = elaborate(&mut driver, &mut names, checked, entry);
+        if driver.has_errors() {
+            return Err(anyhow!("aborting due to previous errors"));
+        }
+
+        let roots: Vec<_> = if emit_kind.is_library() {
+            exports.clone()
+        } else {
+            entry.into_iter().collect()
+        };
+
+        let (mut types, context, decls) = elaborate(&mut driver, &mut names, checked, &roots);
+
+        if args.command.build() && emit_kind.is_library() {
+            let output = args.options().output();
+            let name = library_name(&output);
+            let code = emit_library(
+                &mut driver,
+                &mut names,
+                &mut types,
+                &context,
+                &name,
+                &exports,
+                decls,
+            );
+
+            if driver.has_errors() {
+                return Err(anyhow!("aborting due to previous errors"));
+            }
 
-        if args.command.build() {
+            let start = Instant::now();
+            compile_library(&args, &target, &output, emit_kind, code)?;
+            driver.report_pass(Pass::Compile, start.elapsed());
+        } else if args.command.build() {
             let code = emit(&mut driver, &mut names, &mut types, &context, entry, decls);
-            let exec = compile(&args, &target, code)?;
+
+            if driver.has_errors() {
+                return Err(anyhow!("aborting due to previous errors"));
+            }
+
+            let start = Instant::now();
+            let exec = compile(&args, &target, &args.options().output(), code)?;
+            driver.report_pass(Pass::Compile, start.elapsed());
 
             if args.command.run() {
                 let status = Command::new(exec).args(args.options.drain(..)).status()?;
@@ -0,0 +1,138 @@
+//! The project manifest, `zippy.toml`, which lets a project declare the
+//! options it is built with instead of passing them on every invocation.
+//!
+//! ```toml
+//! [package]
+//! name = "example"
+//! main = "src/main.z"
+//! roots = ["lib"]
+//! entry = "main"
+//!
+//! [build]
+//! target = "x86_64-unknown-linux-gnu"
+//! artifacts = "artifacts"
+//! eval = "full"
+//!
+//! [c]
+//! compiler = "clang"
+//! flags = ["-Wall"]
+//! ```
+//!
+//! Everything but the package name is optional. Paths are relative to the
+//! directory containing the manifest.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use zippy_common::EvalAmount;
+
+use super::input::read_file;
+
+/// The name of the manifest file.
+pub const MANIFEST_NAME: &str = "zippy.toml";
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub package: Package,
+
+    #[serde(default)]
+    pub build: Build,
+
+    #[serde(default)]
+    pub c: Compiler,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Package {
+    pub name: String,
+
+    /// The root file of the program.
+    #[serde(default = "default_main")]
+    pub main: PathBuf,
+
+    /// Directories to look for imported files in, if they can't be found
+    /// next to the importing file.
+    #[serde(default)]
+    pub roots: Vec<PathBuf>,
+
+    /// The name of the entry point.
+    #[serde(default = "default_entry")]
+    pub entry: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Build {
+    pub target: Option<String>,
+    pub artifacts: Option<PathBuf>,
+
+    #[serde(default)]
+    pub eval: Eval,
+}
+
+/// How much partial evaluation to do.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Eval {
+    #[default]
+    Full,
+    Types,
+    None,
+}
+
+impl From<Eval> for EvalAmount {
+    fn from(eval: Eval) -> Self {
+        match eval {
+            Eval::Full => EvalAmount::Full,
+            Eval::Types => EvalAmount::Types,
+            Eval::None => EvalAmount::None,
+        }
+    }
+}
+
+/// Settings for the C compiler used to build the generated code.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Compiler {
+    /// The compiler to use instead of the default one for the target.
+    pub compiler: Option<PathBuf>,
+
+    /// Extra flags to pass to the compiler.
+    #[serde(default)]
+    pub flags: Vec<String>,
+}
+
+impl Manifest {
+    /// Read the manifest in the given directory. Every path in the returned
+    /// manifest is relative to the current directory rather than the manifest.
+    pub fn read(dir: &Path) -> anyhow::Result<Self> {
+        let path = dir.join(MANIFEST_NAME);
+        let text =
+            read_file(&path).map_err(|e| anyhow!("could not read '{}': {e}", path.display()))?;
+
+        let mut manifest: Manifest = toml::from_str(&text)
+            .map_err(|e| anyhow!("invalid manifest '{}': {e}", path.display()))?;
+
+        manifest.package.main = dir.join(&manifest.package.main);
+        for root in manifest.package.roots.iter_mut() {
+            *root = dir.join(&*root);
+        }
+
+        if let Some(artifacts) = manifest.build.artifacts.as_mut() {
+            *artifacts = dir.join(&*artifacts);
+        }
+
+        Ok(manifest)
+    }
+}
+
+fn default_main() -> PathBuf {
+    PathBuf::from("main.z")
+}
+
+fn default_entry() -> String {
+    "main".into()
+}
@@ -0,0 +1,179 @@
+//! Machine-readable renderings of diagnostics. Spans are given both as byte
+//! offsets and as one-based lines and columns, where columns are counted in
+//! characters.
+
+use std::collections::BTreeSet;
+
+use clap::ValueEnum;
+use codespan_reporting::files::{Files, SimpleFiles};
+use serde_json::{json, Value};
+
+use zippy_common::message::{Diagnostic, Label, LabelStyle, Severity, Span};
+
+/// How diagnostics are reported.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum MessageFormat {
+    /// Rich messages showing the relevant source code.
+    #[default]
+    Human,
+
+    /// A single line for every message.
+    Short,
+
+    /// A JSON object on a single line for every message.
+    Json,
+
+    /// A single SARIF log containing every message.
+    Sarif,
+}
+
+/// Render a diagnostic as a JSON object.
+pub fn json(files: &SimpleFiles<String, String>, diagnostic: &Diagnostic) -> Value {
+    let labels: Vec<_> = diagnostic
+        .labels
+        .iter()
+        .map(|label| json_label(files, label))
+        .collect();
+
+    json!({
+        "severity": severity_name(diagnostic.severity),
+        "code": diagnostic.code,
+        "message": diagnostic.message,
+        "labels": labels,
+        "notes": diagnostic.notes,
+    })
+}
+
+/// Render the given diagnostics as a SARIF log with a single run.
+pub fn sarif(files: &SimpleFiles<String, String>, diagnostics: &[Diagnostic]) -> Value {
+    let rules: BTreeSet<_> = diagnostics
+        .iter()
+        .filter_map(|diagnostic| diagnostic.code.as_ref())
+        .collect();
+
+    let rules: Vec<_> = rules
+        .into_iter()
+        .map(|code| json!({ "id": code }))
+        .collect();
+
+    let results: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| sarif_result(files, diagnostic))
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "zc",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn json_label(files: &SimpleFiles<String, String>, label: &Label) -> Value {
+    let style = match label.style {
+        LabelStyle::Primary => "primary",
+        LabelStyle::Secondary => "secondary",
+    };
+
+    let file = files
+        .get(label.span.file)
+        .ok()
+        .map(|file| file.name().clone());
+    let (start, end) = position(files, label.span);
+
+    json!({
+        "style": style,
+        "file": file,
+        "message": label.message,
+        "start": start,
+        "end": end,
+    })
+}
+
+fn sarif_result(files: &SimpleFiles<String, String>, diagnostic: &Diagnostic) -> Value {
+    let level = match diagnostic.severity {
+        Severity::Bug | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note | Severity::Help => "note",
+    };
+
+    let mut text = diagnostic.message.clone();
+    for note in diagnostic.notes.iter() {
+        text.push('\n');
+        text.push_str(note);
+    }
+
+    let locations = |style: LabelStyle| -> Vec<_> {
+        diagnostic
+            .labels
+            .iter()
+            .filter(|label| label.style == style)
+            .map(|label| sarif_location(files, label))
+            .collect()
+    };
+
+    json!({
+        "ruleId": diagnostic.code,
+        "level": level,
+        "message": { "text": text },
+        "locations": locations(LabelStyle::Primary),
+        "relatedLocations": locations(LabelStyle::Secondary),
+    })
+}
+
+fn sarif_location(files: &SimpleFiles<String, String>, label: &Label) -> Value {
+    let uri = files
+        .get(label.span.file)
+        .ok()
+        .map(|file| file.name().clone());
+    let (start, end) = position(files, label.span);
+
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": uri },
+            "region": {
+                "byteOffset": label.span.start,
+                "byteLength": label.span.end - label.span.start,
+                "startLine": start["line"],
+                "startColumn": start["column"],
+                "endLine": end["line"],
+                "endColumn": end["column"],
+            },
+        },
+        "message": { "text": label.message },
+    })
+}
+
+/// Get the start and end positions of a span as JSON objects with the byte
+/// offset, line and column of each.
+fn position(files: &SimpleFiles<String, String>, span: Span) -> (Value, Value) {
+    let at = |byte: usize| match files.location(span.file, byte) {
+        Ok(location) => json!({
+            "byte": byte,
+            "line": location.line_number,
+            "column": location.column_number,
+        }),
+
+        Err(_) => json!({ "byte": byte }),
+    };
+
+    (at(span.start), at(span.end))
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug => "bug",
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "note",
+        Severity::Help => "help",
+    }
+}
@@ -0,0 +1,194 @@
+//! An interactive session for evaluating expressions. Declarations entered in
+//! the session are kept around, so later expressions can refer to them. Every
+//! input is checked as part of a single program in one salsa database, so
+//! only the work affected by the new input is actually redone.
+//!
+//! An expression is evaluated by wrapping it in a `test` declaration, which
+//! gives it a name nobody can refer to, and then partially evaluating it. This
+//! means that only expressions which can be evaluated at compile time can be
+//! shown.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use console::style;
+
+use zippy_common::message::File;
+use zippy_common::Driver;
+use zippy_frontend::{
+    check, collect_files, ide, lex, Database, ParseResult, Project, SourceProgram,
+};
+use zippy_midend::{elaborate, static_value};
+
+use super::args::Arguments;
+use super::console_driver::ConsoleDriver;
+
+/// The keywords which start a declaration rather than an expression.
+const DECLARATION_KEYWORDS: [&str; 7] =
+    ["fun", "let", "type", "import", "test", "extern", "export"];
+
+pub fn repl(args: &Arguments) -> anyhow::Result<()> {
+    let mut driver = ConsoleDriver::new(args);
+    let file = driver.add_file(Path::new("<repl>"), String::new());
+
+    let db = Database::default();
+    let root = SourceProgram::new(&db, String::new(), file);
+    let project = Project::new(&db, vec![root], HashMap::new());
+
+    let mut repl = Repl {
+        db,
+        driver,
+        project,
+        root,
+        file,
+        programs: HashMap::from([(file, root)]),
+        declarations: String::new(),
+    };
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        let Some(input) = read_input(&mut lines)? else {
+            break;
+        };
+
+        match input.trim() {
+            "" => continue,
+            ":quit" | ":q" => break,
+            _ => {}
+        }
+
+        let first = input.split_whitespace().next().unwrap_or_default();
+        if DECLARATION_KEYWORDS.contains(&first) {
+            repl.declare(input);
+        } else {
+            repl.evaluate(&input);
+        }
+    }
+
+    Ok(())
+}
+
+struct Repl {
+    db: Database,
+    driver: ConsoleDriver,
+    project: Project,
+    root: SourceProgram,
+    file: File,
+
+    /// The input for every file read so far.
+    programs: HashMap<File, SourceProgram>,
+
+    /// The source text of every declaration entered so far.
+    declarations: String,
+}
+
+impl Repl {
+    /// Add a declaration to the session, as long as it checks without errors.
+    fn declare(&mut self, input: String) {
+        let source = format!("{}{input}\n", self.declarations);
+        self.check(source.clone());
+
+        if !self.driver.has_errors() {
+            self.declarations = source;
+        }
+    }
+
+    /// Check and evaluate an expression, and print its value and type.
+    fn evaluate(&mut self, input: &str) {
+        let header = "test \"\" =\n    ";
+        let offset = self.declarations.len() + header.len();
+        let body = input.lines().collect::<Vec<_>>().join("\n    ");
+        let source = format!("{}{header}{body}\n", self.declarations);
+
+        let ParseResult {
+            checked,
+            mut names,
+            tests,
+            ..
+        } = self.check(source);
+
+        if self.driver.has_errors() {
+            return;
+        }
+
+        let Some(test) = tests.last() else {
+            return;
+        };
+
+        let roots = [test.name];
+        let (_, _, decls) = elaborate(&mut self.driver, &mut names, checked, &roots);
+
+        if self.driver.has_errors() {
+            return;
+        }
+
+        let ty = ide::definition_type(&self.db, self.project, self.file, offset)
+            .unwrap_or_else(|| "?".into());
+
+        match static_value(&decls, &test.name) {
+            Some(values) => {
+                let values: Vec<_> = values.iter().map(ToString::to_string).collect();
+                println!("{} : {ty}", values.join(", "));
+            }
+
+            None => println!(
+                "{}: the value of this expression is only known at runtime",
+                style("note").green()
+            ),
+        }
+    }
+
+    /// Check the session with the given source text.
+    fn check(&mut self, source: String) -> ParseResult {
+        self.driver.clear_errors();
+        self.driver.set_source(self.file, source.clone());
+        self.root.set_text(&mut self.db).to(source);
+
+        let (db, driver, programs) = (&self.db, &mut self.driver, &mut self.programs);
+        let (files, imports, _) = collect_files(db, self.root, |from, name| {
+            let (id, text) = driver.load_import(from, name)?;
+            let program = programs
+                .entry(id)
+                .or_insert_with(|| SourceProgram::new(db, text, id));
+            Some(*program)
+        });
+
+        if self.project.files(&self.db) != &files {
+            self.project.set_files(&mut self.db).to(files);
+        }
+
+        if self.project.imports(&self.db) != &imports {
+            self.project.set_imports(&mut self.db).to(imports);
+        }
+
+        check(&self.db, &mut self.driver, self.project, self.file)
+    }
+}
+
+/// Read a single input, which continues over several lines as long as the
+/// code is incomplete. Returns `None` once the input has ended.
+fn read_input(lines: &mut impl Iterator<Item = io::Result<String>>) -> io::Result<Option<String>> {
+    let mut input = String::new();
+    let mut prompt = "> ";
+
+    loop {
+        print!("{prompt}");
+        io::stdout().flush()?;
+
+        let Some(line) = lines.next().transpose()? else {
+            return Ok((!input.is_empty()).then_some(input));
+        };
+
+        input.push_str(&line);
+        input.push('\n');
+
+        if !lex::is_incomplete(&input) {
+            return Ok(Some(input));
+        }
+
+        prompt = "... ";
+    }
+}
@@ -7,8 +7,8 @@ use target_lexicon::Triple;
 use crate::args::Arguments;
 
 pub fn get_target(args: &Arguments) -> Triple {
-    let target = match args.options().target {
-        Some(ref target) => Triple::from_str(target),
+    let target = match args.command.options().and_then(|opts| opts.target.as_ref()) {
+        Some(target) => Triple::from_str(target),
         None => Ok(Triple::host()),
     };
 
@@ -0,0 +1,117 @@
+//! Running the tests declared in a program with `test "name" = expr`. Tests
+//! which the partial evaluator manages to reduce completely are reported
+//! straight away, while the rest are compiled into a harness which runs one
+//! test per process.
+
+use std::process::Command;
+use std::time::Instant;
+
+use anyhow::anyhow;
+use console::style;
+use target_lexicon::Triple;
+
+use zippy_backend::c::emit_tests;
+use zippy_common::message::{File, Messages};
+use zippy_common::{Driver, Pass};
+use zippy_frontend::{parse, ParseResult};
+use zippy_midend::{elaborate, test_outcome};
+
+use super::args::Arguments;
+use super::compile::compile;
+use super::console_driver::ConsoleDriver;
+
+pub fn run_tests(
+    args: &Arguments,
+    target: &Triple,
+    driver: &mut ConsoleDriver,
+    source: String,
+    file: File,
+) -> anyhow::Result<()> {
+    let ParseResult {
+        checked,
+        mut names,
+        tests,
+        ..
+    } = parse(driver, source, file);
+
+    if driver.has_errors() {
+        return Err(anyhow!("aborting due to previous errors"));
+    }
+
+    println!("running {} tests", tests.len());
+
+    if tests.is_empty() {
+        return Ok(());
+    }
+
+    let roots: Vec<_> = tests.iter().map(|test| test.name).collect();
+    let (mut types, context, decls) = elaborate(driver, &mut names, checked, &roots);
+
+    // For every test, whether it passed and whether that was decided at
+    // compile time.
+    let mut outcomes: Vec<_> = tests
+        .iter()
+        .map(|test| test_outcome(&decls, &test.name).map(|passed| (passed, true)))
+        .collect();
+
+    let dynamic: Vec<_> = tests
+        .iter()
+        .zip(outcomes.iter())
+        .filter(|(_, outcome)| outcome.is_none())
+        .map(|(test, _)| test.name)
+        .collect();
+
+    if !dynamic.is_empty() {
+        let code = emit_tests(driver, &mut names, &mut types, &context, &dynamic, decls);
+
+        if driver.has_errors() {
+            return Err(anyhow!("aborting due to previous errors"));
+        }
+
+        let path = args.options().output().with_extension("test.z");
+        let start = Instant::now();
+        let exec = compile(args, target, &path, code)?;
+        driver.report_pass(Pass::Compile, start.elapsed());
+
+        let pending = outcomes.iter_mut().filter(|outcome| outcome.is_none());
+        for (index, outcome) in pending.enumerate() {
+            let status = Command::new(&exec).arg(index.to_string()).status()?;
+            *outcome = Some((status.success(), false));
+        }
+    }
+
+    let mut messages = Messages::new();
+    let mut failed = 0;
+
+    for (test, outcome) in tests.iter().zip(outcomes) {
+        let (passed, evaluated) = outcome.expect("every test has been run");
+        let when = if evaluated { " (at compile time)" } else { "" };
+
+        let result = if passed {
+            style("ok").green()
+        } else {
+            style("FAILED").red()
+        };
+
+        println!("test {} ... {result}{when}", test.description);
+
+        if !passed {
+            messages
+                .at(test.span)
+                .test_failed(&test.description, evaluated);
+            failed += 1;
+        }
+    }
+
+    driver.report(messages);
+
+    let passed = tests.len() - failed;
+    println!("\ntest result: {passed} passed; {failed} failed");
+
+    if failed > 0 {
+        let plural = if failed == 1 { "" } else { "s" };
+        Err(anyhow!("{failed} test{plural} failed"))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+//! Checking a program again whenever it changes. The salsa database is kept
+//! alive between checks, so only the queries affected by an edit are actually
+//! recomputed. Files are polled for changes rather than watched through the
+//! operating system, which keeps things simple and portable.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::anyhow;
+use console::{style, Term};
+use log::info;
+use target_lexicon::Triple;
+
+use zippy_backend::c::{emit, emit_library};
+use zippy_common::message::File;
+use zippy_common::{Driver, Pass};
+use zippy_frontend::{check, collect_files, Database, ParseResult, Project, SourceProgram};
+use zippy_midend::elaborate;
+
+use super::args::{Arguments, WatchOptions};
+use super::compile::{compile, compile_library, library_name};
+use super::console_driver::ConsoleDriver;
+use super::input::{read_file, Source};
+
+/// How long to wait between checking the files for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+pub fn watch(args: &Arguments, opts: &WatchOptions, target: &Triple) -> anyhow::Result<()> {
+    if opts.run && opts.options.emit_kind().is_library() {
+        return Err(anyhow!("cannot run a library, only build it"));
+    }
+
+    let Source::File(path) = opts.options.source() else {
+        return Err(anyhow!(
+            "can only watch files, not code from stdin or the command line"
+        ));
+    };
+
+    let source = read_file(path)?;
+
+    let mut driver = ConsoleDriver::new(args);
+    let root = driver.add_file(path, source.clone());
+
+    let db = Database::default();
+    let project = Project::new(&db, Vec::new(), HashMap::new());
+    let programs = HashMap::from([(root, SourceProgram::new(&db, source, root))]);
+
+    let mut watcher = Watcher {
+        db,
+        driver,
+        project,
+        root,
+        programs,
+        modified: HashMap::new(),
+    };
+
+    loop {
+        Term::stderr().clear_screen()?;
+
+        if let Err(e) = watcher.recheck(args, opts, target) {
+            eprintln!("{}: {e}", style("error").red());
+        }
+
+        eprintln!("{}: waiting for changes", style("note").green());
+        watcher.wait()?;
+    }
+}
+
+struct Watcher {
+    db: Database,
+    driver: ConsoleDriver,
+    project: Project,
+    root: File,
+
+    /// The input for every file read so far.
+    programs: HashMap<File, SourceProgram>,
+
+    /// When each file was last modified.
+    modified: HashMap<PathBuf, SystemTime>,
+}
+
+impl Watcher {
+    /// Check the project, and build or run it if asked to.
+    fn recheck(
+        &mut self,
+        args: &Arguments,
+        opts: &WatchOptions,
+        target: &Triple,
+    ) -> anyhow::Result<()> {
+        let start = Instant::now();
+        self.driver.clear_errors();
+        self.update_project();
+
+        let ParseResult {
+            checked,
+            mut names,
+            entry,
+            exports,
+            ..
+        } = check(&self.db, &mut self.driver, self.project, self.root);
+
+        info!("checked in {:.2?}", start.elapsed());
+
+        if self.driver.has_errors() || !(opts.build || opts.run) {
+            return Ok(());
+        }
+
+        let start = Instant::now();
+        let driver = &mut self.driver;
+        let emit_kind = opts.options.emit_kind();
+
+        if emit_kind.is_library() {
+            let output = opts.options.output();
+            let name = library_name(&output);
+
+            let (mut types, context, decls) = elaborate(driver, &mut names, checked, &exports);
+            let code = emit_library(
+                driver, &mut names, &mut types, &context, &name, &exports, decls,
+            );
+
+            if !driver.has_errors() {
+                let compiling = Instant::now();
+                compile_library(args, target, &output, emit_kind, code)?;
+                driver.report_pass(Pass::Compile, compiling.elapsed());
+                info!("built in {:.2?}", start.elapsed());
+            }
+
+            return Ok(());
+        }
+
+        let roots: Vec<_> = entry.into_iter().collect();
+        let (mut types, context, decls) = elaborate(driver, &mut names, checked, &roots);
+        let code = emit(driver, &mut names, &mut types, &context, entry, decls);
+
+        if driver.has_errors() {
+            return Ok(());
+        }
+
+        let compiling = Instant::now();
+        let exec = compile(args, target, &opts.options.output(), code)?;
+        driver.report_pass(Pass::Compile, compiling.elapsed());
+        info!("built in {:.2?}", start.elapsed());
+
+        if opts.run {
+            let status = Command::new(exec).args(&args.options).status()?;
+            match status.code() {
+                Some(code) => eprintln!("{}: program quit with code {code}", style("note").green()),
+                None => eprintln!("{}: program terminated by signal", style("note").green()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find every file imported by the root file, and update the project if
+    /// they or the imports between them have changed.
+    fn update_project(&mut self) {
+        let root = self.programs[&self.root];
+        let (db, driver, programs) = (&self.db, &mut self.driver, &mut self.programs);
+
+        let (files, imports, times) = collect_files(db, root, |from, name| {
+            let (id, text) = driver.load_import(from, name)?;
+            let program = programs
+                .entry(id)
+                .or_insert_with(|| SourceProgram::new(db, text, id));
+            Some(*program)
+        });
+
+        times.report(driver);
+
+        // Setting an input always invalidates the queries depending on it, so
+        // only do so if something actually changed.
+        if self.project.files(&self.db) != &files {
+            self.project.set_files(&mut self.db).to(files);
+        }
+
+        if self.project.imports(&self.db) != &imports {
+            self.project.set_imports(&mut self.db).to(imports);
+        }
+
+        for (path, _) in self.driver.paths() {
+            if self.modified.contains_key(path) {
+                continue;
+            }
+
+            if let Ok(modified) = fs::metadata(path).and_then(|meta| meta.modified()) {
+                info!("watching '{}'", path.display());
+                self.modified.insert(path.to_path_buf(), modified);
+            }
+        }
+    }
+
+    /// Wait until any of the files have changed, and update their text.
+    fn wait(&mut self) -> anyhow::Result<()> {
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            let changed = self.changed();
+            if changed.is_empty() {
+                continue;
+            }
+
+            for (path, file) in changed {
+                info!("'{}' changed", path.display());
+
+                // The file may be in the middle of being written, in which
+                // case it will be picked up on the next change.
+                let Ok(source) = read_file(&path) else {
+                    continue;
+                };
+
+                self.driver.set_source(file, source.clone());
+                if let Some(program) = self.programs.get(&file) {
+                    program.set_text(&mut self.db).to(source);
+                }
+            }
+
+            return Ok(());
+        }
+    }
+
+    /// Get every file whose modification time has changed since the last time
+    /// this was called.
+    fn changed(&mut self) -> Vec<(PathBuf, File)> {
+        let mut changed = Vec::new();
+
+        for (path, file) in self.driver.paths() {
+            let Ok(modified) = fs::metadata(path).and_then(|meta| meta.modified()) else {
+                continue;
+            };
+
+            if self.modified.get(path) != Some(&modified) {
+                self.modified.insert(path.to_path_buf(), modified);
+                changed.push((path.to_path_buf(), file));
+            }
+        }
+
+        changed
+    }
+}